@@ -175,9 +175,19 @@ impl<'a> X3DExporter<'a> {
                 Material::None => {}
             }
 
-            // write mesh
+            // write mesh, whereby meshes with morph targets are written in their static pose
             let mesh = part.get_mesh();
-            Self::write_mesh(writer, &mesh)?;
+            match mesh.get_morph_targets() {
+                Some(morph_targets) if !morph_targets.has_zero_weights() => {
+                    match mesh.to_static_pose() {
+                        Ok(posed_mesh) => Self::write_mesh(writer, &posed_mesh)?,
+                        Err(err) => {
+                            warn!("Skipping writing geometry due to {}", err);
+                        }
+                    }
+                }
+                _ => Self::write_mesh(writer, &mesh)?,
+            }
 
             Ok(())
         })?;
@@ -257,11 +267,13 @@ impl<'a> X3DExporter<'a> {
 
 #[cfg(test)]
 mod tests {
-    use std::io::Cursor;
+    use std::{io::Cursor, path::PathBuf};
 
     use itertools::EitherOrBoth;
 
-    use crate::loader::{loader_off::LoaderOff, Loader, MemoryResource};
+    use crate::loader::{
+        loader_gltf::LoaderGLTF, loader_off::LoaderOff, FileResource, Loader, MemoryResource,
+    };
 
     use super::*;
 
@@ -314,4 +326,26 @@ mod tests {
 
         // let m = extract_cube_data(s.as_str());
     }
+
+    #[test]
+    fn test_x3d_writer_morph_targets() {
+        let r = FileResource::new(
+            PathBuf::from("src/loader/test_data/gltf/MorphTriangle.gltf"),
+            "model/gltf+json",
+        );
+        let cad_data = LoaderGLTF::new().read(&r).unwrap();
+
+        let mut data: Vec<u8> = Vec::new();
+        {
+            let c = Cursor::new(&mut data);
+            let x = X3DExporter::new(&cad_data);
+            x.write(c).unwrap();
+        }
+
+        let s = String::from_utf8(data).unwrap();
+
+        // the base node has zero weights and the posed node has the weights [1, 0.5]
+        assert!(s.contains("<Coordinate point=\"0 0 0 1 0 0 0 1 0\"/>"));
+        assert!(s.contains("<Coordinate point=\"0.5 1 0 1 1 0 0 2 0\"/>"));
+    }
 }
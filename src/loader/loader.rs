@@ -2,7 +2,7 @@ use std::{path::Path, collections::{BTreeMap, BTreeSet}};
 
 use crate::{error::Error, structure::CADData};
 
-use super::{FileResource, Resource};
+use super::{FileResource, LoadReport, Resource};
 
 pub type ExtensionMap = BTreeMap<String, BTreeSet<String>>;
 
@@ -28,6 +28,18 @@ pub trait Loader {
     /// * `reader` - The reader from which the loader will read the cad data.
     fn read(&self, resource: &dyn Resource) -> Result<CADData, Error>;
 
+    /// Reads the CAD data from the given reader and additionally returns a report of all
+    /// non-fatal problems. In contrast to `read`, a loader may drop invalid primitives and record
+    /// them in the report instead of failing. If something happens, the loader will return a
+    /// error message.
+    ///
+    /// # Arguments
+    /// * `reader` - The reader from which the loader will read the cad data.
+    fn read_with_report(&self, resource: &dyn Resource) -> Result<(CADData, LoadReport), Error> {
+        let cad_data = self.read(resource)?;
+        Ok((cad_data, LoadReport::new()))
+    }
+
     /// Reads the CAD data from the given path. If something happens, the loader will return
     /// a error message.
     ///
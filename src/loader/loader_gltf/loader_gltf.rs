@@ -5,36 +5,76 @@ use std::{
 };
 
 use gltf::{
-    accessor::{DataType as GLTFDataType, Dimensions},
+    accessor::{
+        sparse::{IndexType, Sparse},
+        DataType as GLTFDataType, Dimensions,
+    },
     buffer::{Source, View},
+    image::Source as ImageSource,
     iter::Buffers,
     material::AlphaMode,
     mesh::{iter::Attributes, Mode},
     scene::Transform,
     Accessor, Document, Gltf, Material as GLTFMaterial, Mesh as GLTFMesh, Node as GLTFNode,
-    Primitive as GLTFPrimitive, Semantic,
+    Primitive as GLTFPrimitive, Semantic, Texture as GLTFTexture,
 };
 use log::{debug, warn};
-use nalgebra_glm::{Mat4, Vec3};
+use nalgebra_glm::{Mat4, Vec2, Vec3};
 
 use crate::{
-    loader::{Loader, Resource},
+    loader::{
+        normals_mode::apply_normals_mode, DefaultMaterialResolver, LoadReport, Loader,
+        MaterialFallbackReason, MaterialRequest, MaterialResolver, NormalsMode, PrimitiveFailure,
+        Resource,
+    },
     structure::{
-        CADData, IndexData, Material, Mesh, Node, Normals, PhongMaterialData, Positions,
-        PrimitiveType, Primitives, Shape, ShapePart, Vertices,
+        CADData, IndexData, Material, Mesh, MorphTarget, MorphTargets, Node, Normals,
+        PhongMaterialData, Positions, PrimitiveType, Primitives, Shape, ShapePart, TexCoords,
+        TextureReference, Vertices,
     },
-    Color, Error, RGB,
+    Error, RGB,
 };
 
 use super::{accessor_iterator::AccessorIterator, component::ComponentTrait, utils::transmute_vec};
 
+/// The maximal number of texture coordinate channels which are read, i.e., TEXCOORD_0 and
+/// TEXCOORD_1.
+const MAX_TEX_COORD_CHANNELS: u32 = 2;
+
 /// A loader for GLTF 2.0
 /// Specification: See `<https://www.khronos.org/gltf/>`
-pub struct LoaderGLTF {}
+///
+/// Primitives with invalid indices are handled differently by the two read functions: `read`
+/// fails with an error, whereas `read_with_report` drops these primitives and lists them in the
+/// load report.
+pub struct LoaderGLTF {
+    material_resolver: Rc<dyn MaterialResolver>,
+    normals_mode: NormalsMode,
+}
 
 impl LoaderGLTF {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            material_resolver: Rc::new(DefaultMaterialResolver::new()),
+            normals_mode: NormalsMode::default(),
+        }
+    }
+
+    /// Sets the material resolver which provides the materials for all primitives whose
+    /// material cannot be resolved from the GLTF.
+    ///
+    /// # Arguments
+    /// * `material_resolver` - The material resolver consulted for the fallback materials.
+    pub fn set_material_resolver(&mut self, material_resolver: Rc<dyn MaterialResolver>) {
+        self.material_resolver = material_resolver;
+    }
+
+    /// Sets the mode that defines how the normals are handled after the geometry has been read.
+    ///
+    /// # Arguments
+    /// * `normals_mode` - The mode for handling the normals.
+    pub fn set_normals_mode(&mut self, normals_mode: NormalsMode) {
+        self.normals_mode = normals_mode;
     }
 
     /// Resolves the buffer references for the specified GLTF.
@@ -88,18 +128,58 @@ impl LoaderGLTF {
         Ok(blobs)
     }
 
-    /// Creates CAD data based on the provided document and blobs.
+    /// Reads the CAD data and the load report from the given resource.
+    ///
+    /// # Arguments
+    /// * `resource` - The resource from which the GLTF is read.
+    /// * `drop_invalid_primitives` - If true, primitives with invalid indices are dropped and
+    ///   reported instead of failing.
+    fn read_gltf(
+        &self,
+        resource: &dyn Resource,
+        drop_invalid_primitives: bool,
+    ) -> Result<(CADData, LoadReport), Error> {
+        let buffer = resource.read_to_memory()?;
+
+        let gltf_data = match Gltf::from_slice(&buffer) {
+            Ok(g) => g,
+            Err(err) => {
+                return Err(Error::InvalidFormat(format!(
+                    "Failed reading GLTF due to {}",
+                    err
+                )));
+            }
+        };
+
+        let d = gltf_data.document;
+
+        let buffers = Self::resolve_buffers(resource, d.buffers(), gltf_data.blob)?;
+        debug!("Got {} buffers", buffers.len());
+
+        self.create_cad_data(d, buffers, drop_invalid_primitives)
+    }
+
+    /// Creates CAD data and the load report based on the provided document and blobs.
     ///
     /// # Arguments
     /// * `document` - The GLTF document
     /// * `blobs` - The buffers associated with the GLTF.
-    fn create_cad_data(document: Document, blobs: Vec<Vec<u8>>) -> Result<CADData, Error> {
-        let creator = CADDataCreator::new();
+    /// * `drop_invalid_primitives` - If true, primitives with invalid indices are dropped and
+    ///   reported instead of failing.
+    fn create_cad_data(
+        &self,
+        document: Document,
+        blobs: Vec<Vec<u8>>,
+        drop_invalid_primitives: bool,
+    ) -> Result<(CADData, LoadReport), Error> {
+        let creator = CADDataCreator::new(self.material_resolver.clone(), drop_invalid_primitives);
 
         let gltf_data = GLTFData { document, blobs };
-        let cad_data = creator.create(&gltf_data)?;
+        let (cad_data, report) = creator.create(&gltf_data)?;
 
-        Ok(cad_data)
+        let cad_data = apply_normals_mode(cad_data, self.normals_mode)?;
+
+        Ok((cad_data, report))
     }
 }
 
@@ -132,24 +212,12 @@ impl Loader for LoaderGLTF {
     }
 
     fn read(&self, resource: &dyn Resource) -> Result<CADData, Error> {
-        let buffer = resource.read_to_memory()?;
-
-        let gltf_data = match Gltf::from_slice(&buffer) {
-            Ok(g) => g,
-            Err(err) => {
-                return Err(Error::InvalidFormat(format!(
-                    "Failed reading GLTF due to {}",
-                    err
-                )));
-            }
-        };
-
-        let d = gltf_data.document;
-
-        let buffers = Self::resolve_buffers(resource, d.buffers(), gltf_data.blob)?;
-        debug!("Got {} buffers", buffers.len());
+        let (cad_data, _) = self.read_gltf(resource, false)?;
+        Ok(cad_data)
+    }
 
-        Self::create_cad_data(d, buffers)
+    fn read_with_report(&self, resource: &dyn Resource) -> Result<(CADData, LoadReport), Error> {
+        self.read_gltf(resource, true)
     }
 }
 
@@ -158,39 +226,65 @@ struct GLTFData {
     pub blobs: Vec<Vec<u8>>,
 }
 
+/// The parts of a GLTF mesh whose materials are resolved for each node by the material resolver.
+struct MaterialFallbacks {
+    /// The label of the GLTF mesh.
+    mesh_label: String,
+
+    /// The fallback reason for each part of the shape. Is none if the part has its own material.
+    reasons: Vec<Option<MaterialFallbackReason>>,
+
+    /// The already created shape instances by the addresses of their resolved materials. The
+    /// addresses stay valid, as the shapes keep their materials alive.
+    instances: HashMap<Vec<*const Material>, Rc<Shape>>,
+}
+
 struct CADDataCreator {
     shape_map: HashMap<usize, Rc<Shape>>,
     material_map: HashMap<usize, Rc<Material>>,
+    material_fallbacks: HashMap<usize, MaterialFallbacks>,
+    material_resolver: Rc<dyn MaterialResolver>,
+    drop_invalid_primitives: bool,
+    report: LoadReport,
 }
 
 impl CADDataCreator {
     /// Returns a new empty CAD data creator object.
-    pub fn new() -> Self {
+    ///
+    /// # Arguments
+    /// * `material_resolver` - The resolver consulted for materials missing in the GLTF.
+    /// * `drop_invalid_primitives` - If true, primitives with invalid indices are dropped and
+    ///   reported instead of failing.
+    pub fn new(material_resolver: Rc<dyn MaterialResolver>, drop_invalid_primitives: bool) -> Self {
         Self {
             shape_map: HashMap::new(),
             material_map: HashMap::new(),
+            material_fallbacks: HashMap::new(),
+            material_resolver,
+            drop_invalid_primitives,
+            report: LoadReport::new(),
         }
     }
 
-    /// Creates the CAD-Data from the given GLTF data.
+    /// Creates the CAD-Data and the load report from the given GLTF data.
     ///
     /// # Arguments
     /// * `gltf_data` - The GLTF data used for creating the overall CAD data.
-    pub fn create(self, gltf_data: &GLTFData) -> Result<CADData, Error> {
+    pub fn create(self, gltf_data: &GLTFData) -> Result<(CADData, LoadReport), Error> {
         let mut creator = self;
 
         creator.create_materials(gltf_data)?;
         creator.create_shapes(gltf_data)?;
         let root_node = creator.create_nodes(gltf_data)?;
 
-        Ok(CADData::new(root_node))
+        Ok((CADData::new(root_node), creator.report))
     }
 
     /// Creates a tree from all GLTF scenes and data.
     ///
     /// # Arguments
     /// * `gltf_data` - The GLTF data which is used for parsing and creating the tree.
-    fn create_nodes(&self, gltf_data: &GLTFData) -> Result<Node, Error> {
+    fn create_nodes(&mut self, gltf_data: &GLTFData) -> Result<Node, Error> {
         // iterate over the list of GLTF scenes and create a node for each scene
         let scenes = gltf_data.document.scenes();
         let mut root_nodes: Vec<Node> = Vec::with_capacity(scenes.len());
@@ -229,13 +323,13 @@ impl CADDataCreator {
     /// # Arguments
     /// * `gltf_data` - The GLTF data which is used for parsing and creating the tree.
     /// * `in_node` - The gltf node which defines the subtree.
-    fn process_node(&self, gltf_data: &GLTFData, in_node: GLTFNode) -> Result<Node, Error> {
+    fn process_node(&mut self, gltf_data: &GLTFData, in_node: GLTFNode) -> Result<Node, Error> {
         let label = match in_node.name() {
             Some(s) => s.to_owned(),
             None => "".to_owned(),
         };
 
-        let mut out_node = Node::new(label);
+        let mut out_node = Node::new(label.clone());
 
         // set the matrix for the node
         let m = Self::transform_to_matrix(in_node.transform());
@@ -247,7 +341,17 @@ impl CADDataCreator {
                 let mesh_index = mesh.index();
                 match self.shape_map.get(&mesh_index) {
                     Some(shape) => {
-                        out_node.attach_shape(shape.clone());
+                        let shape =
+                            self.resolve_fallback_materials(mesh_index, shape.clone(), &label);
+                        match in_node.weights() {
+                            Some(weights) => {
+                                let shape = Self::create_shape_with_weights(&shape, weights)?;
+                                out_node.attach_shape(Rc::new(shape));
+                            }
+                            None => {
+                                out_node.attach_shape(shape);
+                            }
+                        }
                     }
                     None => {
                         return Err(Error::InvalidFormat(format!(
@@ -269,6 +373,93 @@ impl CADDataCreator {
         Ok(out_node)
     }
 
+    /// Returns the given shape of the GLTF mesh with the fallback materials resolved for the
+    /// given node. Instances with the same resolved materials are shared between the nodes.
+    ///
+    /// # Arguments
+    /// * `mesh_index` - The index of the GLTF mesh the shape has been created from.
+    /// * `shape` - The shape created from the GLTF mesh.
+    /// * `node_label` - The label of the node which instantiates the shape.
+    fn resolve_fallback_materials(
+        &mut self,
+        mesh_index: usize,
+        shape: Rc<Shape>,
+        node_label: &str,
+    ) -> Rc<Shape> {
+        let fallbacks = match self.material_fallbacks.get_mut(&mesh_index) {
+            Some(fallbacks) => fallbacks,
+            None => return shape,
+        };
+
+        let materials: Vec<Rc<Material>> = shape
+            .get_parts()
+            .iter()
+            .zip(fallbacks.reasons.iter())
+            .map(|(part, reason)| match reason {
+                Some(reason) => self.material_resolver.resolve(&MaterialRequest {
+                    node_label,
+                    mesh_label: Some(&fallbacks.mesh_label),
+                    reason: *reason,
+                }),
+                None => part.get_material(),
+            })
+            .collect();
+
+        // reuse an existing instance if the resolver returned the same materials
+        let key: Vec<*const Material> = materials.iter().map(Rc::as_ptr).collect();
+        fallbacks
+            .instances
+            .entry(key)
+            .or_insert_with(|| {
+                let mut out_shape = Shape::new();
+                for (part, material) in shape.get_parts().iter().zip(materials) {
+                    out_shape.add_part(ShapePart::new(part.get_mesh(), material));
+                }
+
+                Rc::new(out_shape)
+            })
+            .clone()
+    }
+
+    /// Returns a copy of the given shape whose morph target weights are replaced by the given
+    /// weights. Parts without morph targets are shared with the given shape. If the number of
+    /// weights does not match the number of morph targets, an error is returned.
+    ///
+    /// # Arguments
+    /// * `shape` - The shape to copy.
+    /// * `weights` - The morph target weights of the node which instantiates the shape.
+    fn create_shape_with_weights(shape: &Shape, weights: &[f32]) -> Result<Shape, Error> {
+        let mut out_shape = Shape::new();
+
+        for part in shape.get_parts() {
+            let mesh = part.get_mesh();
+            let mesh = match mesh.get_morph_targets() {
+                Some(morph_targets) => {
+                    let num_targets = morph_targets.get_targets().len();
+                    if weights.len() != num_targets {
+                        return Err(Error::InvalidFormat(format!(
+                            "Node defines {} morph target weights, but the mesh has {} morph targets",
+                            weights.len(),
+                            num_targets
+                        )));
+                    }
+
+                    let mut morph_targets = morph_targets.clone();
+                    morph_targets.set_weights(weights.to_vec())?;
+
+                    let mut out_mesh = mesh.as_ref().clone();
+                    out_mesh.set_morph_targets(morph_targets)?;
+                    Rc::new(out_mesh)
+                }
+                None => mesh,
+            };
+
+            out_shape.add_part(ShapePart::new(mesh, part.get_material()));
+        }
+
+        Ok(out_shape)
+    }
+
     /// Returns a matrix 4 from the given GLTF transformation.
     ///
     /// # Arguments
@@ -294,7 +485,8 @@ impl CADDataCreator {
         Ok(())
     }
 
-    /// Creates a phong material from the given PBR material.
+    /// Creates a phong material from the given PBR material. The base color, emissive, normal and
+    /// occlusion textures are referenced, the metallic-roughness texture is not supported.
     ///
     /// # Arguments
     /// * `material` - The GLTF material used for creating the phong material
@@ -320,53 +512,73 @@ impl CADDataCreator {
         let mut phong_data = PhongMaterialData::default();
         phong_data.diffuse_color = diffuse_color;
         phong_data.transparency = 1f32 - alpha_value;
+        phong_data.diffuse_texture = material
+            .pbr_metallic_roughness()
+            .base_color_texture()
+            .map(|info| Self::create_texture_reference(info.texture(), info.tex_coord()));
+        phong_data.emissive_texture = material
+            .emissive_texture()
+            .map(|info| Self::create_texture_reference(info.texture(), info.tex_coord()));
+        phong_data.normal_texture = material
+            .normal_texture()
+            .map(|info| Self::create_texture_reference(info.texture(), info.tex_coord()));
+        phong_data.occlusion_texture = material
+            .occlusion_texture()
+            .map(|info| Self::create_texture_reference(info.texture(), info.tex_coord()));
 
         Ok(Material::PhongMaterial(phong_data))
     }
 
-    /// Returns the default material. If it doesn't exists, it will be created.
-    fn get_default_material(&mut self) -> Rc<Material> {
-        let default_material_index = usize::MAX;
-
-        match self.material_map.get(&default_material_index) {
-            Some(m) => m.clone(),
-            None => {
-                let mut phong_data: PhongMaterialData = Default::default();
-                phong_data.diffuse_color = RGB::black();
-                let default_material = Rc::new(Material::PhongMaterial(phong_data));
+    /// Creates a texture reference from the given GLTF texture. A warning is emitted if the
+    /// texture uses a texture coordinate channel which is not read.
+    ///
+    /// # Arguments
+    /// * `texture` - The GLTF texture which references the image.
+    /// * `tex_coord` - The index of the texture coordinate channel used by the texture.
+    fn create_texture_reference(texture: GLTFTexture, tex_coord: u32) -> TextureReference {
+        let uri = match texture.source().source() {
+            ImageSource::Uri { uri, .. } => Some(uri.to_owned()),
+            ImageSource::View { .. } => None,
+        };
 
-                self.material_map
-                    .insert(default_material_index, default_material.clone());
+        if tex_coord >= MAX_TEX_COORD_CHANNELS {
+            warn!(
+                "Texture {} uses texture coordinate channel {}, but only {} channels are read",
+                texture.index(),
+                tex_coord,
+                MAX_TEX_COORD_CHANNELS
+            );
+        }
 
-                default_material
-            }
+        TextureReference {
+            uri,
+            tex_coord_channel: tex_coord as usize,
         }
     }
 
-    /// Returns a material for the given GLTF material. If the material cannot be found, a warning
-    /// is emitted and the default material is returned instead.
+    /// Returns a material for the given GLTF material. If the material cannot be found, the
+    /// reason is returned instead, i.e., the material is resolved later for each node by the
+    /// material resolver.
     ///
     /// # Arguments
     ///
     /// * `material` - The GLTF material to translate to material.
-    fn get_material(&mut self, material: GLTFMaterial) -> Rc<Material> {
+    fn get_material(&self, material: GLTFMaterial) -> Result<Rc<Material>, MaterialFallbackReason> {
         // check if the given GLTF material has an index defined
         let index = match material.index() {
             Some(index) => index,
-            None => return self.get_default_material(),
+            None => return Err(MaterialFallbackReason::NoMaterial),
         };
 
         // use index to lookup the material
         match self.material_map.get(&index) {
-            Some(m) => {
-                return m.clone();
-            }
+            Some(m) => Ok(m.clone()),
             None => {
                 warn!(
-                    "Cannot find material with index {}. Take default material",
+                    "Cannot find material with index {}. Ask the material resolver",
                     index
                 );
-                return self.get_default_material();
+                Err(MaterialFallbackReason::MissingIndex(index))
             }
         }
     }
@@ -388,7 +600,8 @@ impl CADDataCreator {
         Ok(())
     }
 
-    /// Creates a shape from of the given GLTF mesh.
+    /// Creates a shape from of the given GLTF mesh. If enabled, primitives with invalid indices
+    /// are dropped and recorded in the load report. All other errors are fatal.
     ///
     /// # Arguments
     /// * `gltf_data` - The overall loaded GLTF data.
@@ -396,21 +609,88 @@ impl CADDataCreator {
     fn create_shape(&mut self, mesh: GLTFMesh, gltf_data: &GLTFData) -> Result<Shape, Error> {
         let mut shape = Shape::new();
 
+        let mesh_label = match mesh.name() {
+            Some(s) => s.to_owned(),
+            None => mesh.index().to_string(),
+        };
+
+        // parts without material get a placeholder until the fallbacks are resolved per node
+        let placeholder = Rc::new(Material::None);
+        let mut reasons = Vec::new();
+
         let primitives = mesh.primitives();
         for primitive in primitives {
-            let material = self.get_material(primitive.material());
+            match Self::create_mesh(gltf_data, primitive.clone(), mesh.weights()) {
+                Ok(mesh) => {
+                    let material = match self.get_material(primitive.material()) {
+                        Ok(material) => {
+                            reasons.push(None);
+                            material
+                        }
+                        Err(reason) => {
+                            reasons.push(Some(reason));
+                            placeholder.clone()
+                        }
+                    };
+
+                    shape.add_part(ShapePart::new(Rc::new(mesh), material));
+                }
+                Err(err @ Error::Indices(_)) if self.drop_invalid_primitives => {
+                    let mesh_path = format!("{}/{}", mesh.index(), primitive.index());
+                    warn!(
+                        "Dropping primitive {} of mesh {} due to {}",
+                        primitive.index(),
+                        mesh_label,
+                        err
+                    );
+
+                    self.report.add_primitive_failure(PrimitiveFailure {
+                        primitive_type: Self::translate_primitive_mode(primitive.mode()),
+                        mesh_path,
+                        mesh_name: mesh.name().map(|s| s.to_owned()),
+                        error: err,
+                    });
+                }
+                Err(err) => return Err(err),
+            }
+        }
 
-            // create the mesh primitive data
-            let primitive_type = Self::translate_primitive_mode(primitive.mode());
-            let index_data = Self::create_index_data(gltf_data, primitive.clone())?;
-            let mesh_primitives = Primitives::new(index_data, primitive_type)?;
+        if reasons.iter().any(|r| r.is_some()) {
+            self.material_fallbacks.insert(
+                mesh.index(),
+                MaterialFallbacks {
+                    mesh_label,
+                    reasons,
+                    instances: HashMap::new(),
+                },
+            );
+        }
 
-            // create positions
-            let positions: Positions = match Self::find_accessor_by_semantic(
-                primitive.attributes(),
-                Semantic::Positions,
-            ) {
-                Some(accessor) => transmute_vec(Self::create_vec3_data(gltf_data, accessor)?),
+        Ok(shape)
+    }
+
+    /// Creates a mesh from the given GLTF primitive.
+    ///
+    /// # Arguments
+    /// * `gltf_data` - The overall loaded GLTF data.
+    /// * `primitive` - The GLTF primitive that is parsed to create the mesh.
+    /// * `weights` - The optional default weights of the morph targets of the GLTF mesh.
+    fn create_mesh(
+        gltf_data: &GLTFData,
+        primitive: GLTFPrimitive,
+        weights: Option<&[f32]>,
+    ) -> Result<Mesh, Error> {
+        // create the mesh primitive data
+        let primitive_type = Self::translate_primitive_mode(primitive.mode());
+        let index_data = Self::create_index_data(gltf_data, primitive.clone())?;
+        let mesh_primitives = Primitives::new(index_data, primitive_type)?;
+
+        // create positions
+        let positions: Positions =
+            match Self::find_accessor_by_semantic(primitive.attributes(), Semantic::Positions) {
+                Some(accessor) => {
+                    transmute_vec(Self::create_vec_data(gltf_data, accessor, Vec3::from)?)
+                }
                 None => {
                     return Err(Error::InvalidFormat(format!(
                         "Missing position attribute for the primitive data"
@@ -418,31 +698,87 @@ impl CADDataCreator {
                 }
             };
 
-            let num_vertices = positions.len();
-            let mut vertices = Vertices::from_positions(positions);
+        let num_vertices = positions.len();
+        let mut vertices = Vertices::from_positions(positions);
 
-            match Self::find_accessor_by_semantic(primitive.attributes(), Semantic::Normals) {
-                Some(accessor) => {
-                    let normals: Normals =
-                        transmute_vec(Self::create_vec3_data(gltf_data, accessor)?);
-                    if normals.len() != num_vertices {
-                        return Err(Error::InvalidFormat(format!(
-                            "Number of positions {} do not match number of normals {}",
-                            num_vertices,
-                            normals.len()
-                        )));
-                    }
-
-                    vertices.set_normals(normals)?;
+        match Self::find_accessor_by_semantic(primitive.attributes(), Semantic::Normals) {
+            Some(accessor) => {
+                let normals: Normals =
+                    transmute_vec(Self::create_vec_data(gltf_data, accessor, Vec3::from)?);
+                if normals.len() != num_vertices {
+                    return Err(Error::InvalidFormat(format!(
+                        "Number of positions {} do not match number of normals {}",
+                        num_vertices,
+                        normals.len()
+                    )));
                 }
-                None => {}
+
+                vertices.set_normals(normals)?;
             }
+            None => {}
+        }
 
-            let mesh = Mesh::new(vertices, mesh_primitives)?;
-            shape.add_part(ShapePart::new(Rc::new(mesh), material));
+        for channel in 0..MAX_TEX_COORD_CHANNELS {
+            let accessor = match Self::find_accessor_by_semantic(
+                primitive.attributes(),
+                Semantic::TexCoords(channel),
+            ) {
+                Some(accessor) => accessor,
+                None => break,
+            };
+
+            let tex_coords: TexCoords =
+                transmute_vec(Self::create_vec_data(gltf_data, accessor, Vec2::from)?);
+            if tex_coords.len() != num_vertices {
+                return Err(Error::InvalidFormat(format!(
+                    "Number of positions {} do not match number of texture coordinates {}",
+                    num_vertices,
+                    tex_coords.len()
+                )));
+            }
+
+            vertices.set_tex_coords(channel as usize, tex_coords)?;
         }
 
-        Ok(shape)
+        let mut mesh = Mesh::new(vertices, mesh_primitives)?;
+
+        // create the morph targets
+        let mut targets = Vec::new();
+        for target in primitive.morph_targets() {
+            let position_deltas: Option<Positions> = match target.positions() {
+                Some(accessor) => Some(transmute_vec(Self::create_morph_target_deltas(
+                    gltf_data, accessor,
+                )?)),
+                None => None,
+            };
+
+            let normal_deltas: Option<Normals> = match target.normals() {
+                Some(accessor) => Some(transmute_vec(Self::create_morph_target_deltas(
+                    gltf_data, accessor,
+                )?)),
+                None => None,
+            };
+
+            targets.push(MorphTarget::new(position_deltas, normal_deltas));
+        }
+
+        if !targets.is_empty() {
+            let weights = match weights {
+                Some(weights) if weights.len() != targets.len() => {
+                    return Err(Error::InvalidFormat(format!(
+                        "Mesh defines {} morph target weights, but the primitive has {} morph targets",
+                        weights.len(),
+                        targets.len()
+                    )));
+                }
+                Some(weights) => weights.to_vec(),
+                None => vec![0f32; targets.len()],
+            };
+
+            mesh.set_morph_targets(MorphTargets::new(targets, weights)?)?;
+        }
+
+        Ok(mesh)
     }
 
     /// Tries to find an accessor with the specified semantic.
@@ -570,15 +906,209 @@ impl CADDataCreator {
         Ok(indices)
     }
 
-    /// Creates vector 3 data from the given accessor.
+    /// Creates the displacements of a morph target from the given accessor. Accessors without a
+    /// buffer view are initialized with zeros and sparse substitutions are applied on top.
+    /// Note: Accessors with neither a buffer view nor sparse substitutions are already rejected
+    /// while parsing the GLTF document.
+    ///
+    /// # Arguments
+    /// * `gltf_data` - The overall loaded GLTF data.
+    /// * `accessor` - The accessor that is used for the displacements.
+    fn create_morph_target_deltas(
+        gltf_data: &GLTFData,
+        accessor: Accessor,
+    ) -> Result<Vec<Vec3>, Error> {
+        let mut deltas = match accessor.view() {
+            Some(_) => Self::create_vec_data(gltf_data, accessor.clone(), Vec3::from)?,
+            None => {
+                if accessor.dimensions() != Dimensions::Vec3 {
+                    return Err(Error::InvalidFormat(format!(
+                        "Dimension is not 3, but {}",
+                        accessor.dimensions().multiplicity()
+                    )));
+                }
+
+                vec![Vec3::zeros(); accessor.count()]
+            }
+        };
+
+        if let Some(sparse) = accessor.sparse() {
+            let indices = Self::extract_sparse_indices(gltf_data, &sparse)?;
+            let values = match accessor.data_type() {
+                GLTFDataType::U8 => Self::extract_sparse_vecs3::<u8>(gltf_data, &accessor, &sparse),
+                GLTFDataType::U16 => {
+                    Self::extract_sparse_vecs3::<u16>(gltf_data, &accessor, &sparse)
+                }
+                GLTFDataType::U32 => {
+                    Self::extract_sparse_vecs3::<u32>(gltf_data, &accessor, &sparse)
+                }
+                GLTFDataType::I8 => Self::extract_sparse_vecs3::<i8>(gltf_data, &accessor, &sparse),
+                GLTFDataType::I16 => {
+                    Self::extract_sparse_vecs3::<i16>(gltf_data, &accessor, &sparse)
+                }
+                GLTFDataType::F32 => {
+                    Self::extract_sparse_vecs3::<f32>(gltf_data, &accessor, &sparse)
+                }
+            }?;
+
+            for (index, value) in indices.iter().zip(values) {
+                match deltas.get_mut(*index as usize) {
+                    Some(delta) => *delta = value,
+                    None => {
+                        return Err(Error::InvalidFormat(format!(
+                            "Invalid sparse index {}",
+                            index
+                        )));
+                    }
+                }
+            }
+        }
+
+        Ok(deltas)
+    }
+
+    /// Returns the buffer referenced by the given view, whereby the given number of bytes
+    /// starting at the given offset within the view must be inside the buffer.
+    ///
+    /// # Arguments
+    /// * `gltf_data` - The overall loaded GLTF data.
+    /// * `view` - The view onto the buffer.
+    /// * `offset` - The offset within the view.
+    /// * `num_bytes` - The number of bytes which are read.
+    fn get_sparse_buffer<'a>(
+        gltf_data: &'a GLTFData,
+        view: &View,
+        offset: usize,
+        num_bytes: usize,
+    ) -> Result<&'a [u8], Error> {
+        let buffer_index = view.buffer().index();
+        if buffer_index >= gltf_data.blobs.len() {
+            return Err(Error::InvalidFormat(format!(
+                "Invalid buffer index {}",
+                buffer_index
+            )));
+        }
+
+        let buffer = gltf_data.blobs[buffer_index].as_slice();
+        if view.offset() + offset + num_bytes > buffer.len() {
+            return Err(Error::InvalidFormat(format!(
+                "Sparse data with {} bytes exceeds buffer {}",
+                num_bytes, buffer_index
+            )));
+        }
+
+        Ok(buffer)
+    }
+
+    /// Extracts the indices of the sparse substitutions.
+    ///
+    /// # Arguments
+    /// * `gltf_data` - The overall loaded GLTF data.
+    /// * `sparse` - The sparse storage of the accessor.
+    fn extract_sparse_indices(gltf_data: &GLTFData, sparse: &Sparse) -> Result<Vec<u32>, Error> {
+        let count = sparse.count();
+        let indices = sparse.indices();
+        let view = indices.view();
+        let buffer = Self::get_sparse_buffer(
+            gltf_data,
+            &view,
+            indices.offset(),
+            count * indices.index_type().size(),
+        )?;
+
+        let indices = match indices.index_type() {
+            IndexType::U8 => AccessorIterator::<u8>::new_detail(
+                buffer,
+                count,
+                0,
+                GLTFDataType::U8,
+                Dimensions::Scalar,
+                indices.offset(),
+                view.offset(),
+            )
+            .map(|i| i as u32)
+            .collect(),
+            IndexType::U16 => AccessorIterator::<u16>::new_detail(
+                buffer,
+                count,
+                0,
+                GLTFDataType::U16,
+                Dimensions::Scalar,
+                indices.offset(),
+                view.offset(),
+            )
+            .map(|i| i as u32)
+            .collect(),
+            IndexType::U32 => AccessorIterator::<u32>::new_detail(
+                buffer,
+                count,
+                0,
+                GLTFDataType::U32,
+                Dimensions::Scalar,
+                indices.offset(),
+                view.offset(),
+            )
+            .collect(),
+        };
+
+        Ok(indices)
+    }
+
+    /// Extracts the vector 3 values of the sparse substitutions.
+    ///
+    /// # Arguments
+    /// * `gltf_data` - The overall loaded GLTF data.
+    /// * `accessor` - The accessor which defines the type of the values.
+    /// * `sparse` - The sparse storage of the accessor.
+    fn extract_sparse_vecs3<T>(
+        gltf_data: &GLTFData,
+        accessor: &Accessor,
+        sparse: &Sparse,
+    ) -> Result<Vec<Vec3>, Error>
+    where
+        T: ComponentTrait + Display,
+    {
+        let normalize = accessor.normalized();
+        let count = sparse.count();
+        let values = sparse.values();
+        let view = values.view();
+        let buffer = Self::get_sparse_buffer(
+            gltf_data,
+            &view,
+            values.offset(),
+            count * std::mem::size_of::<[T; 3]>(),
+        )?;
+
+        let it = AccessorIterator::<[T; 3]>::new_detail(
+            buffer,
+            count,
+            0,
+            accessor.data_type(),
+            Dimensions::Vec3,
+            values.offset(),
+            view.offset(),
+        );
+
+        Ok(it
+            .map(|x| Vec3::from(x.map(|c| c.to_f32(normalize))))
+            .collect())
+    }
+
+    /// Creates vector data with N components from the given accessor.
     ///
     /// # Arguments
     /// * `gltf_data` - The overall loaded GLTF data.
     /// * `accessor` - The accessor that is used for the data.
-    fn create_vec3_data(gltf_data: &GLTFData, accessor: Accessor) -> Result<Vec<Vec3>, Error> {
-        if accessor.dimensions().multiplicity() != 3 {
+    /// * `to_vec` - Converts the components read as f32 into the resulting vector.
+    fn create_vec_data<const N: usize, V>(
+        gltf_data: &GLTFData,
+        accessor: Accessor,
+        to_vec: fn([f32; N]) -> V,
+    ) -> Result<Vec<V>, Error> {
+        if accessor.dimensions().multiplicity() != N {
             return Err(Error::InvalidFormat(format!(
-                "Dimension is not 3, but {}",
+                "Dimension is not {}, but {}",
+                N,
                 accessor.dimensions().multiplicity()
             )));
         }
@@ -586,37 +1116,39 @@ impl CADDataCreator {
         let view = match accessor.view() {
             Some(view) => view,
             None => {
-                return Err(Error::InvalidFormat(format!(
-                    "Missing buffer view reference"
-                )));
+                return Err(Error::InvalidFormat(
+                    "Missing buffer view reference".to_owned(),
+                ));
             }
         };
 
         let vecs = match accessor.data_type() {
-            GLTFDataType::U8 => Self::extract_vecs3::<u8>(gltf_data, accessor, view),
-            GLTFDataType::U16 => Self::extract_vecs3::<u16>(gltf_data, accessor, view),
-            GLTFDataType::U32 => Self::extract_vecs3::<u32>(gltf_data, accessor, view),
-            GLTFDataType::I8 => Self::extract_vecs3::<i8>(gltf_data, accessor, view),
-            GLTFDataType::I16 => Self::extract_vecs3::<i16>(gltf_data, accessor, view),
-            GLTFDataType::F32 => Self::extract_vecs3::<f32>(gltf_data, accessor, view),
+            GLTFDataType::U8 => Self::extract_vecs::<u8, N, V>(gltf_data, accessor, view, to_vec),
+            GLTFDataType::U16 => Self::extract_vecs::<u16, N, V>(gltf_data, accessor, view, to_vec),
+            GLTFDataType::U32 => Self::extract_vecs::<u32, N, V>(gltf_data, accessor, view, to_vec),
+            GLTFDataType::I8 => Self::extract_vecs::<i8, N, V>(gltf_data, accessor, view, to_vec),
+            GLTFDataType::I16 => Self::extract_vecs::<i16, N, V>(gltf_data, accessor, view, to_vec),
+            GLTFDataType::F32 => Self::extract_vecs::<f32, N, V>(gltf_data, accessor, view, to_vec),
         }?;
 
         Ok(vecs)
     }
 
-    /// Extracts the vector 3 from the given accessor and related buffer view.
+    /// Extracts the vectors with N components from the given accessor and related buffer view.
     ///
     /// # Arguments
     /// * `gltf_data` - The overall loaded GLTF data.
     /// * `accessor` - The accessor used for extracting the data.
     /// * `view` - The buffer that defines the view onto the data.
-    fn extract_vecs3<T: ComponentTrait>(
+    /// * `to_vec` - Converts the components read as f32 into the resulting vector.
+    fn extract_vecs<T, const N: usize, V>(
         gltf_data: &GLTFData,
         accessor: Accessor,
         view: View,
-    ) -> Result<Vec<Vec3>, Error>
+        to_vec: fn([f32; N]) -> V,
+    ) -> Result<Vec<V>, Error>
     where
-        T: Sized + Copy + Display,
+        T: ComponentTrait + Display,
     {
         let normalize = accessor.normalized();
 
@@ -630,24 +1162,18 @@ impl CADDataCreator {
 
         let buffer = gltf_data.blobs[buffer_index].as_ref();
 
-        let mut vecs: Vec<Vec3> = Vec::with_capacity(accessor.count());
-        let it = AccessorIterator::<[T; 3]>::new(buffer, view, accessor.clone());
+        let mut vecs: Vec<V> = Vec::with_capacity(accessor.count());
+        let it = AccessorIterator::<[T; N]>::new(buffer, view, accessor.clone());
 
         for x in it {
-            let v = Vec3::new(
-                x[0].to_f32(normalize),
-                x[1].to_f32(normalize),
-                x[2].to_f32(normalize),
-            );
-
-            vecs.push(v);
+            vecs.push(to_vec(x.map(|c| c.to_f32(normalize))));
         }
 
         if vecs.len() != accessor.count() {
             return Err(Error::InvalidFormat(format!(
                 "Read {} values, but should have been {}",
                 vecs.len(),
-                accessor.count() * 3
+                accessor.count()
             )));
         }
 
@@ -699,11 +1225,14 @@ impl CADDataCreator {
 
 #[cfg(test)]
 mod tests {
-    use std::{path::PathBuf, str::FromStr};
+    use std::{cell::RefCell, path::PathBuf, str::FromStr};
 
     use nalgebra_glm::cross;
 
-    use crate::{loader::FileResource, structure::Point3D};
+    use crate::{
+        loader::FileResource,
+        structure::{Point3D, TexCoord},
+    };
 
     use super::*;
 
@@ -817,4 +1346,470 @@ mod tests {
         let cad_data = loader.read(&r).unwrap();
         test_if_it_is_a_box(&cad_data);
     }
+
+    #[test]
+    fn test_gltf_two_tex_coord_channels() {
+        let r = FileResource::new(
+            PathBuf::from_str("src/loader/test_data/gltf/TwoUVs.gltf").unwrap(),
+            "model/gltf+json",
+        );
+
+        let loader = LoaderGLTF::new();
+        let cad_data = loader.read(&r).unwrap();
+
+        let shape = find_shape(cad_data.get_root_node()).unwrap();
+        assert_eq!(shape.get_parts().len(), 1);
+        let part = &shape.get_parts()[0];
+
+        // check that both texture coordinate channels have been imported
+        let mesh = part.get_mesh();
+        let vertices = mesh.get_vertices();
+        assert_eq!(vertices.len(), 4);
+        assert_eq!(vertices.num_tex_coord_channels(), 2);
+
+        let uv0 = vertices.get_tex_coords(0).unwrap();
+        assert_eq!(
+            uv0,
+            &[
+                TexCoord::new(0f32, 0f32),
+                TexCoord::new(1f32, 0f32),
+                TexCoord::new(1f32, 1f32),
+                TexCoord::new(0f32, 1f32),
+            ]
+        );
+
+        let uv1 = vertices.get_tex_coords(1).unwrap();
+        assert_eq!(
+            uv1,
+            &[
+                TexCoord::new(0.25f32, 0.25f32),
+                TexCoord::new(0.75f32, 0.25f32),
+                TexCoord::new(0.75f32, 0.75f32),
+                TexCoord::new(0.25f32, 0.75f32),
+            ]
+        );
+
+        // check that the material records the channel of each texture
+        let material = part.get_material();
+        let phong_data = match material.as_ref() {
+            Material::PhongMaterial(phong_data) => phong_data,
+            Material::None => panic!("Expected phong material"),
+        };
+
+        let diffuse_texture = phong_data.diffuse_texture.as_ref().unwrap();
+        assert_eq!(diffuse_texture.uri.as_deref(), Some("base_color.png"));
+        assert_eq!(diffuse_texture.tex_coord_channel, 0);
+
+        let normal_texture = phong_data.normal_texture.as_ref().unwrap();
+        assert_eq!(normal_texture.uri.as_deref(), Some("normal.png"));
+        assert_eq!(normal_texture.tex_coord_channel, 0);
+
+        let occlusion_texture = phong_data.occlusion_texture.as_ref().unwrap();
+        assert_eq!(occlusion_texture.uri.as_deref(), Some("lightmap.png"));
+        assert_eq!(occlusion_texture.tex_coord_channel, 1);
+
+        // the channel of the emissive texture is recorded, although the channel is not read
+        let emissive_texture = phong_data.emissive_texture.as_ref().unwrap();
+        assert_eq!(emissive_texture.uri.as_deref(), Some("emissive.png"));
+        assert_eq!(emissive_texture.tex_coord_channel, 2);
+    }
+
+    #[test]
+    fn test_gltf_report_primitive_failures() {
+        let r = FileResource::new(
+            PathBuf::from_str("src/loader/test_data/gltf/BoxInvalidPrimitive.gltf").unwrap(),
+            "model/gltf+json",
+        );
+
+        let loader = LoaderGLTF::new();
+        let (cad_data, report) = loader.read_with_report(&r).unwrap();
+
+        // the valid primitive is still loaded
+        test_if_it_is_a_box(&cad_data);
+
+        // the primitive with an invalid number of triangle indices is reported
+        let failures = report.get_primitive_failures();
+        assert_eq!(failures.len(), 1);
+
+        let failure = &failures[0];
+        assert_eq!(failure.primitive_type, PrimitiveType::Triangles);
+        assert_eq!(failure.mesh_path, "0/1");
+        assert_eq!(failure.mesh_name.as_deref(), Some("Mesh"));
+        assert!(matches!(failure.error, Error::Indices(_)));
+
+        // a valid file results into an empty report
+        let r = FileResource::new(
+            PathBuf::from_str("src/loader/test_data/gltf/Box.gltf").unwrap(),
+            "model/gltf+json",
+        );
+
+        let (_, report) = loader.read_with_report(&r).unwrap();
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn test_gltf_read_fails_on_invalid_primitive() {
+        let r = FileResource::new(
+            PathBuf::from_str("src/loader/test_data/gltf/BoxInvalidPrimitive.gltf").unwrap(),
+            "model/gltf+json",
+        );
+
+        // reading without a report doesn't drop any primitives
+        let loader = LoaderGLTF::new();
+        assert!(matches!(loader.read(&r), Err(Error::Indices(_))));
+    }
+
+    #[test]
+    fn test_gltf_morph_targets() {
+        let r = FileResource::new(
+            PathBuf::from_str("src/loader/test_data/gltf/MorphTriangle.gltf").unwrap(),
+            "model/gltf+json",
+        );
+
+        let loader = LoaderGLTF::new();
+        let cad_data = loader.read(&r).unwrap();
+
+        let children = cad_data.get_root_node().get_children();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].get_label(), "Base");
+        assert_eq!(children[1].get_label(), "Posed");
+
+        // check the base and the deltas of the morph targets
+        let base_mesh = children[0].get_shapes()[0].get_parts()[0].get_mesh();
+        let morph_targets = base_mesh.get_morph_targets().unwrap();
+        assert_eq!(morph_targets.get_weights(), [0f32, 0f32]);
+
+        let targets = morph_targets.get_targets();
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].get_position_deltas().unwrap().len(), 3);
+        assert_eq!(targets[0].get_normal_deltas().unwrap().len(), 3);
+        assert_eq!(targets[1].get_position_deltas().unwrap().len(), 3);
+        assert!(targets[1].get_normal_deltas().is_none());
+
+        // applying the first target with weight 1 moves all vertices up by one
+        let morphed = base_mesh.apply_morph_targets(&[1f32, 0f32]).unwrap();
+        assert!(morphed.get_morph_targets().is_none());
+        assert_eq!(
+            morphed.get_vertices().get_positions(),
+            &[
+                Point3D::new(0f32, 1f32, 0f32),
+                Point3D::new(1f32, 1f32, 0f32),
+                Point3D::new(0f32, 2f32, 0f32),
+            ]
+        );
+
+        let expected_normal = Vec3::new(1f32, 0f32, 1f32).normalize();
+        for n in morphed.get_vertices().get_normals().unwrap() {
+            assert!((n.0 - expected_normal).norm() < 1e-6f32);
+        }
+
+        assert!(base_mesh.apply_morph_targets(&[1f32]).is_err());
+
+        // the static pose of the base node is the base mesh as all weights are zero
+        let static_pose = base_mesh.to_static_pose().unwrap();
+        assert_eq!(
+            static_pose.get_vertices().get_positions(),
+            base_mesh.get_vertices().get_positions()
+        );
+
+        // the node weights override the weights of the mesh
+        let posed_mesh = children[1].get_shapes()[0].get_parts()[0].get_mesh();
+        assert_eq!(
+            posed_mesh.get_morph_targets().unwrap().get_weights(),
+            [1f32, 0.5f32]
+        );
+
+        let static_pose = posed_mesh.to_static_pose().unwrap();
+        assert_eq!(
+            static_pose.get_vertices().get_positions(),
+            &[
+                Point3D::new(0.5f32, 1f32, 0f32),
+                Point3D::new(1f32, 1f32, 0f32),
+                Point3D::new(0f32, 2f32, 0f32),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gltf_morph_targets_generated_normals() {
+        let r = FileResource::new(
+            PathBuf::from_str("src/loader/test_data/gltf/MorphTriangle.gltf").unwrap(),
+            "model/gltf+json",
+        );
+
+        // the authored normal displacements are only kept together with the authored normals
+        for (mode, has_normal_deltas) in [
+            (NormalsMode::Keep, true),
+            (NormalsMode::Smooth, true),
+            (NormalsMode::Flat, false),
+            (NormalsMode::Recompute, false),
+        ] {
+            let mut loader = LoaderGLTF::new();
+            loader.set_normals_mode(mode);
+            let cad_data = loader.read(&r).unwrap();
+
+            let children = cad_data.get_root_node().get_children();
+            let posed_mesh = children[1].get_shapes()[0].get_parts()[0].get_mesh();
+            let morph_targets = posed_mesh.get_morph_targets().unwrap();
+            assert_eq!(morph_targets.get_weights(), [1f32, 0.5f32]);
+
+            let targets = morph_targets.get_targets();
+            assert_eq!(targets.len(), 2);
+            assert!(targets[0].get_position_deltas().is_some());
+            assert_eq!(targets[0].get_normal_deltas().is_some(), has_normal_deltas);
+            assert!(targets[1].get_normal_deltas().is_none());
+        }
+    }
+
+    #[test]
+    fn test_gltf_morph_target_weights_mismatch() {
+        let loader = LoaderGLTF::new();
+
+        // the weights of the mesh and of the node must match the number of morph targets
+        for file in [
+            "MorphTriangleBadMeshWeights.gltf",
+            "MorphTriangleBadNodeWeights.gltf",
+        ] {
+            let r = FileResource::new(
+                PathBuf::from("src/loader/test_data/gltf").join(file),
+                "model/gltf+json",
+            );
+
+            assert!(matches!(loader.read(&r), Err(Error::InvalidFormat(_))));
+            assert!(matches!(
+                loader.read_with_report(&r),
+                Err(Error::InvalidFormat(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_gltf_sparse_morph_targets() {
+        let r = FileResource::new(
+            PathBuf::from_str("src/loader/test_data/gltf/MorphSparse.gltf").unwrap(),
+            "model/gltf+json",
+        );
+
+        let loader = LoaderGLTF::new();
+        let cad_data = loader.read(&r).unwrap();
+
+        let mesh = find_shape(cad_data.get_root_node()).unwrap().get_parts()[0].get_mesh();
+        let targets = mesh.get_morph_targets().unwrap().get_targets();
+        assert_eq!(targets.len(), 1);
+
+        // the accessor has no buffer view, i.e., all deltas are zero except for the substitution
+        assert_eq!(
+            targets[0].get_position_deltas().unwrap(),
+            &[
+                Point3D::new(0f32, 0f32, 0f32),
+                Point3D::new(0f32, 0f32, 0f32),
+                Point3D::new(0f32, 1f32, 0f32),
+            ]
+        );
+    }
+
+    /// A material resolver which records all requests and returns no material.
+    struct RecordingResolver {
+        requests: RefCell<Vec<(String, Option<String>, MaterialFallbackReason)>>,
+    }
+
+    impl MaterialResolver for RecordingResolver {
+        fn resolve(&self, request: &MaterialRequest) -> Rc<Material> {
+            self.requests.borrow_mut().push((
+                request.node_label.to_owned(),
+                request.mesh_label.map(|l| l.to_owned()),
+                request.reason,
+            ));
+            Rc::new(Material::None)
+        }
+    }
+
+    #[test]
+    fn test_gltf_material_resolver() {
+        let r = FileResource::new(
+            PathBuf::from_str("src/loader/test_data/gltf/MorphTriangle.gltf").unwrap(),
+            "model/gltf+json",
+        );
+
+        let resolver = Rc::new(RecordingResolver {
+            requests: RefCell::new(Vec::new()),
+        });
+
+        let mut loader = LoaderGLTF::new();
+        loader.set_material_resolver(resolver.clone());
+        let cad_data = loader.read(&r).unwrap();
+
+        // the single primitive of the mesh doesn't reference any material and is requested for
+        // each node instantiating the mesh
+        let reason = MaterialFallbackReason::NoMaterial;
+        let mesh_label = Some("Triangle".to_owned());
+        assert_eq!(
+            *resolver.requests.borrow(),
+            [
+                ("Base".to_owned(), mesh_label.clone(), reason),
+                ("Posed".to_owned(), mesh_label, reason)
+            ]
+        );
+
+        let part = &cad_data.get_root_node().get_children()[0].get_shapes()[0].get_parts()[0];
+        assert!(matches!(part.get_material().as_ref(), Material::None));
+    }
+
+    /// A material resolver which colors the parts by the label of their node.
+    struct NodeColorResolver {
+        materials: HashMap<String, Rc<Material>>,
+        default_material: Rc<Material>,
+    }
+
+    impl NodeColorResolver {
+        fn new(colors: &[(&str, RGB)]) -> Self {
+            let materials = colors
+                .iter()
+                .map(|(label, color)| {
+                    let phong_data = PhongMaterialData {
+                        diffuse_color: color.clone(),
+                        ..Default::default()
+                    };
+
+                    (
+                        label.to_string(),
+                        Rc::new(Material::PhongMaterial(phong_data)),
+                    )
+                })
+                .collect();
+
+            Self {
+                materials,
+                default_material: Rc::new(Material::None),
+            }
+        }
+    }
+
+    impl MaterialResolver for NodeColorResolver {
+        fn resolve(&self, request: &MaterialRequest) -> Rc<Material> {
+            match self.materials.get(request.node_label) {
+                Some(material) => material.clone(),
+                None => self.default_material.clone(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_gltf_material_resolver_by_node_label() {
+        let r = FileResource::new(
+            PathBuf::from_str("src/loader/test_data/gltf/TriangleInstances.gltf").unwrap(),
+            "model/gltf+json",
+        );
+
+        let red = RGB::new(1f32, 0f32, 0f32);
+        let green = RGB::new(0f32, 1f32, 0f32);
+        let resolver = NodeColorResolver::new(&[("Red", red.clone()), ("Green", green.clone())]);
+
+        let mut loader = LoaderGLTF::new();
+        loader.set_material_resolver(Rc::new(resolver));
+        let cad_data = loader.read(&r).unwrap();
+
+        let children = cad_data.get_root_node().get_children();
+        assert_eq!(children.len(), 4);
+
+        let get_diffuse_color = |node: &Node| -> Option<Vec3> {
+            match node.get_shapes()[0].get_parts()[0].get_material().as_ref() {
+                Material::PhongMaterial(phong_data) => Some(phong_data.diffuse_color.0),
+                Material::None => None,
+            }
+        };
+
+        assert_eq!(children[0].get_label(), "Red");
+        assert_eq!(get_diffuse_color(&children[0]), Some(red.0));
+        assert_eq!(children[1].get_label(), "Green");
+        assert_eq!(get_diffuse_color(&children[1]), Some(green.0));
+        assert_eq!(get_diffuse_color(&children[2]), None);
+        assert_eq!(get_diffuse_color(&children[3]), None);
+
+        // all instances share the mesh and nodes with the same materials share the shape
+        let shapes: Vec<_> = children.iter().map(|c| c.get_shapes()[0].clone()).collect();
+        assert!(Rc::ptr_eq(
+            &shapes[0].get_parts()[0].get_mesh(),
+            &shapes[1].get_parts()[0].get_mesh()
+        ));
+        assert!(!Rc::ptr_eq(&shapes[0], &shapes[1]));
+        assert!(Rc::ptr_eq(&shapes[2], &shapes[3]));
+    }
+
+    #[test]
+    fn test_gltf_material_resolver_many_instances() {
+        let r = FileResource::new(
+            PathBuf::from_str("src/loader/test_data/gltf/TriangleManyInstances.gltf").unwrap(),
+            "model/gltf+json",
+        );
+
+        let red = RGB::new(1f32, 0f32, 0f32);
+        let green = RGB::new(0f32, 1f32, 0f32);
+        let resolver = NodeColorResolver::new(&[("Red", red), ("Green", green)]);
+
+        let mut loader = LoaderGLTF::new();
+        loader.set_material_resolver(Rc::new(resolver));
+        let cad_data = loader.read(&r).unwrap();
+
+        let children = cad_data.get_root_node().get_children();
+        assert_eq!(children.len(), 300);
+
+        // the nodes with the same label share one of the three shapes
+        for (i, child) in children.iter().enumerate() {
+            let shape = &child.get_shapes()[0];
+            let first_shape = &children[i % 3].get_shapes()[0];
+            assert!(Rc::ptr_eq(shape, first_shape));
+        }
+
+        let shapes: Vec<_> = children[..3]
+            .iter()
+            .map(|c| c.get_shapes()[0].clone())
+            .collect();
+        assert!(!Rc::ptr_eq(&shapes[0], &shapes[1]));
+        assert!(!Rc::ptr_eq(&shapes[0], &shapes[2]));
+        assert!(!Rc::ptr_eq(&shapes[1], &shapes[2]));
+    }
+
+    #[test]
+    fn test_world_triangles_of_loaded_group() {
+        let r = FileResource::new(
+            PathBuf::from_str("src/loader/test_data/gltf/Box.gltf").unwrap(),
+            "model/gltf+json",
+        );
+        let cad_data = LoaderGLTF::new().read(&r).unwrap();
+
+        // place the loaded group somewhere else in the world and compose the transformations
+        // along the path to the node of the box
+        let root_node = cad_data.get_root_node();
+        let group_node = &root_node.get_children()[0];
+        let box_node = &group_node.get_children()[0];
+
+        let mut transform = nalgebra_glm::translation(&Vec3::new(1f32, 2f32, 3f32));
+        for node in [root_node, group_node, box_node] {
+            transform *= node.get_transform().unwrap_or(Mat4::identity());
+        }
+
+        let shape = &box_node.get_shapes()[0];
+        let part = &shape.get_parts()[0];
+        let mesh = part.get_mesh();
+        let indices = mesh.get_primitives().to_triangles();
+
+        let triangles: Vec<_> = shape.world_triangles(&transform).collect();
+        assert_eq!(triangles.len(), indices.len());
+        assert_eq!(triangles.len(), 12);
+
+        let positions = mesh.get_vertices().get_positions();
+        for (triangle, t) in triangles.iter().zip(indices.iter()) {
+            assert!(Rc::ptr_eq(&triangle.get_material(), &part.get_material()));
+
+            for (p, i) in triangle.get_positions().iter().zip(t.iter()) {
+                let expected = (transform * positions[*i as usize].0.push(1f32)).xyz();
+                assert!((p.0 - expected).norm() < 1e-6f32);
+            }
+        }
+
+        // the rotated and translated box still has the surface area of an unit cube
+        let area: f32 = triangles.iter().map(|t| t.get_area()).sum();
+        assert!((area - 6f32).abs() < 1e-5f32);
+    }
 }
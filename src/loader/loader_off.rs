@@ -18,7 +18,9 @@ use crate::{
 
 use super::{
     loader::{ExtensionMap, Loader},
-    Resource,
+    normals_mode::apply_normals_mode,
+    DefaultMaterialResolver, MaterialFallbackReason, MaterialRequest, MaterialResolver,
+    NormalsMode, Resource,
 };
 
 use log::{debug, trace};
@@ -28,11 +30,33 @@ type LineWithNumber = (usize, Result<String, IOError>);
 
 /// A loader for OFF (Object File Format)
 /// Specification: See `<https://segeval.cs.princeton.edu/public/off_format.html>`
-pub struct LoaderOff {}
+pub struct LoaderOff {
+    material_resolver: Rc<dyn MaterialResolver>,
+    normals_mode: NormalsMode,
+}
 
 impl LoaderOff {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            material_resolver: Rc::new(DefaultMaterialResolver::new()),
+            normals_mode: NormalsMode::default(),
+        }
+    }
+
+    /// Sets the material resolver which provides the materials, as OFF doesn't define any.
+    ///
+    /// # Arguments
+    /// * `material_resolver` - The material resolver consulted for all parts.
+    pub fn set_material_resolver(&mut self, material_resolver: Rc<dyn MaterialResolver>) {
+        self.material_resolver = material_resolver;
+    }
+
+    /// Sets the mode that defines how the normals are handled after the geometry has been read.
+    ///
+    /// # Arguments
+    /// * `normals_mode` - The mode for handling the normals.
+    pub fn set_normals_mode(&mut self, normals_mode: NormalsMode) {
+        self.normals_mode = normals_mode;
     }
 
     /// Simple wrapper for reading a line from the given lines. Fails if there is no line left
@@ -52,6 +76,29 @@ impl LoaderOff {
         }
     }
 
+    /// Reads the line of the element with the given index, e.g., a vertex or a face. Fails with
+    /// an error pointing at the missing element if there is no line left.
+    ///
+    /// # Arguments
+    /// * `line` - The line to read.
+    /// * `element` - The name of the element, e.g., "vertex".
+    /// * `index` - The index of the element to read.
+    /// * `count` - The declared number of elements.
+    fn read_element_line(
+        line: Option<&LineWithNumber>,
+        element: &str,
+        index: usize,
+        count: usize,
+    ) -> Result<(usize, String), Error> {
+        match line {
+            Some(_) => Self::read_line(line),
+            None => Err(Error::InvalidFormat(format!(
+                "Unexpected end of file. Missing {} {}, but {} have been declared",
+                element, index, count
+            ))),
+        }
+    }
+
     /// Reads a single number from the split string. Fails if the number cannot be parsed or if
     /// there is no further number.
     fn read_number<'a, N, E>(
@@ -121,7 +168,7 @@ impl LoaderOff {
         let mut positions = Positions::with_capacity(num_vertices);
 
         // determine if the we have colors
-        let do_we_have_colors = (Self::read_line(lines.peek())?)
+        let do_we_have_colors = (Self::read_element_line(lines.peek(), "vertex", 0, num_vertices)?)
             .1
             .split_ascii_whitespace()
             .count()
@@ -134,8 +181,13 @@ impl LoaderOff {
         };
 
         // parse vertices
-        for _ in 0..num_vertices {
-            let (line_number, line) = Self::read_line(lines.next().as_ref())?;
+        for vertex_index in 0..num_vertices {
+            let (line_number, line) = Self::read_element_line(
+                lines.next().as_ref(),
+                "vertex",
+                vertex_index,
+                num_vertices,
+            )?;
 
             let mut chunks = line.split_ascii_whitespace();
 
@@ -184,8 +236,9 @@ impl LoaderOff {
         let mut indices: Vec<u32> = Vec::with_capacity(num_faces * 3);
 
         // iterate over faces and create triangle indices
-        for _ in 0..num_faces {
-            let (line_number, line) = Self::read_line(lines.next().as_ref())?;
+        for face_index in 0..num_faces {
+            let (line_number, line) =
+                Self::read_element_line(lines.next().as_ref(), "face", face_index, num_faces)?;
 
             let mut chunks = line.split_ascii_whitespace();
 
@@ -224,17 +277,29 @@ impl LoaderOff {
     }
 
     /// Creates CAD data from the given vertices and primitives.
-    fn create_cad_data(vertices: Vertices, primitives: Primitives) -> Result<CADData, Error> {
+    fn create_cad_data(
+        &self,
+        vertices: Vertices,
+        primitives: Primitives,
+    ) -> Result<CADData, Error> {
         trace!("Create CAD data...");
+        let label = "root";
+
+        // OFF doesn't define any materials, i.e., the material resolver has to provide one
+        let material = self.material_resolver.resolve(&MaterialRequest {
+            node_label: label,
+            mesh_label: None,
+            reason: MaterialFallbackReason::Unspecified,
+        });
 
         // create mesh and shape from the given vertices and primitives
         let mesh = Mesh::new(vertices, primitives)?;
-        let part = ShapePart::new(Rc::new(mesh), Default::default());
+        let part = ShapePart::new(Rc::new(mesh), material);
         let mut shape = Shape::new();
         shape.add_part(part);
 
         // create root node and attach shape to it
-        let mut root_node = Node::new("root".to_owned());
+        let mut root_node = Node::new(label.to_owned());
         root_node.attach_shape(Rc::new(shape));
 
         // finally, create the cad data
@@ -280,17 +345,25 @@ impl Loader for LoaderOff {
         let vertices = Self::read_vertices(&mut lines, num_vertices)?;
 
         let primitives = Self::read_primitives(&mut lines, num_faces, num_vertices)?;
-        let cad_data = Self::create_cad_data(vertices, primitives)?;
 
-        Ok(cad_data)
+        // any trailing data after the declared faces is ignored
+        let cad_data = self.create_cad_data(vertices, primitives)?;
+
+        apply_normals_mode(cad_data, self.normals_mode)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use nalgebra_glm::{cross, Vec3};
 
-    use crate::loader::MemoryResource;
+    use crate::{
+        basic_types::RGB,
+        loader::MemoryResource,
+        structure::{Material, PhongMaterialData},
+    };
 
     use super::*;
 
@@ -379,4 +452,193 @@ mod tests {
         );
         assert!((area - 6f32).abs() <= 1e-6f32);
     }
+
+    #[test]
+    fn test_cube_with_trailing_garbage() {
+        let s = include_str!("test_data/cube_trailing_garbage.off");
+
+        let r = MemoryResource::new(s.as_bytes(), "model/vnd.off".to_owned());
+
+        let loader = LoaderOff::new();
+
+        let cad_data = loader.read(&r).unwrap();
+        let shape = &cad_data.get_root_node().get_shapes()[0];
+        let mesh = shape.get_parts()[0].get_mesh();
+
+        assert_eq!(mesh.get_vertices().len(), 8);
+        assert_eq!(mesh.get_primitives().num_primitives(), 12);
+    }
+
+    #[test]
+    fn test_truncated_vertices() {
+        let s = "OFF\n8 6 0\n-0.5 -0.5 0.5\n0.5 -0.5 0.5\n-0.5 0.5 0.5\n";
+
+        let r = MemoryResource::new(s.as_bytes(), "model/vnd.off".to_owned());
+
+        let loader = LoaderOff::new();
+
+        match loader.read(&r) {
+            Err(Error::InvalidFormat(msg)) => {
+                assert_eq!(
+                    msg,
+                    "Unexpected end of file. Missing vertex 3, but 8 have been declared"
+                );
+            }
+            _ => panic!("Expected invalid format error"),
+        }
+
+        // a file which ends directly after the header misses the first vertex
+        let r = MemoryResource::new("OFF\n8 6 0\n".as_bytes(), "model/vnd.off".to_owned());
+
+        match loader.read(&r) {
+            Err(Error::InvalidFormat(msg)) => {
+                assert_eq!(
+                    msg,
+                    "Unexpected end of file. Missing vertex 0, but 8 have been declared"
+                );
+            }
+            _ => panic!("Expected invalid format error"),
+        }
+    }
+
+    #[test]
+    fn test_truncated_faces() {
+        let s = include_str!("test_data/cube_truncated.off");
+
+        let r = MemoryResource::new(s.as_bytes(), "model/vnd.off".to_owned());
+
+        let loader = LoaderOff::new();
+
+        match loader.read(&r) {
+            Err(Error::InvalidFormat(msg)) => {
+                assert_eq!(
+                    msg,
+                    "Unexpected end of file. Missing face 4, but 6 have been declared"
+                );
+            }
+            _ => panic!("Expected invalid format error"),
+        }
+    }
+
+    /// A material resolver which colors the parts by the label of their node.
+    struct LabelColorResolver {
+        colors: HashMap<String, RGB>,
+    }
+
+    impl MaterialResolver for LabelColorResolver {
+        fn resolve(&self, request: &MaterialRequest) -> Rc<Material> {
+            match self.colors.get(request.node_label) {
+                Some(color) => {
+                    let mut phong_data = PhongMaterialData::default();
+                    phong_data.diffuse_color = color.clone();
+                    Rc::new(Material::PhongMaterial(phong_data))
+                }
+                None => Rc::new(Material::None),
+            }
+        }
+    }
+
+    #[test]
+    fn test_material_resolver() {
+        let s = include_str!("test_data/cube.off");
+
+        let r = MemoryResource::new(s.as_bytes(), "model/vnd.off".to_owned());
+
+        // without a custom resolver the cube has no material
+        let mut loader = LoaderOff::new();
+        let cad_data = loader.read(&r).unwrap();
+        let part = &cad_data.get_root_node().get_shapes()[0].get_parts()[0];
+        assert!(matches!(part.get_material().as_ref(), Material::None));
+
+        // color the root node red
+        let resolver = LabelColorResolver {
+            colors: HashMap::from([("root".to_owned(), RGB::new(1f32, 0f32, 0f32))]),
+        };
+        loader.set_material_resolver(Rc::new(resolver));
+
+        let cad_data = loader.read(&r).unwrap();
+        let part = &cad_data.get_root_node().get_shapes()[0].get_parts()[0];
+        match part.get_material().as_ref() {
+            Material::PhongMaterial(phong_data) => {
+                assert_eq!(phong_data.diffuse_color.0, Vec3::new(1f32, 0f32, 0f32));
+            }
+            Material::None => panic!("Expected phong material"),
+        }
+    }
+
+    /// Loads the OFF cube with the given normals mode and returns its mesh.
+    fn load_cube_with_normals_mode(normals_mode: NormalsMode) -> Rc<Mesh> {
+        let s = include_str!("test_data/cube.off");
+
+        let r = MemoryResource::new(s.as_bytes(), "model/vnd.off".to_owned());
+
+        let mut loader = LoaderOff::new();
+        loader.set_normals_mode(normals_mode);
+
+        let cad_data = loader.read(&r).unwrap();
+        cad_data.get_root_node().get_shapes()[0].get_parts()[0].get_mesh()
+    }
+
+    /// Checks that the normals of the cube corners point into the octant of their corner.
+    fn check_smooth_cube_normals(mesh: &Mesh) {
+        let vertices = mesh.get_vertices();
+        assert_eq!(vertices.len(), 8);
+
+        let normals = vertices.get_normals().unwrap();
+        assert_eq!(normals.len(), 8);
+
+        for (p, n) in vertices.get_positions().iter().zip(normals.iter()) {
+            assert!((n.0.norm() - 1f32).abs() < 1e-6f32);
+            for i in 0..3 {
+                assert_eq!(p.0[i].signum(), n.0[i].signum());
+            }
+        }
+    }
+
+    #[test]
+    fn test_normals_mode() {
+        // keep doesn't generate any normals
+        let mesh = load_cube_with_normals_mode(NormalsMode::Keep);
+        assert_eq!(mesh.get_vertices().len(), 8);
+        assert_eq!(mesh.get_vertices().get_normals(), None);
+
+        // smooth shares the normals between the faces
+        let mesh = load_cube_with_normals_mode(NormalsMode::Smooth);
+        check_smooth_cube_normals(&mesh);
+
+        // flat duplicates the vertices for each face
+        let mesh = load_cube_with_normals_mode(NormalsMode::Flat);
+        let vertices = mesh.get_vertices();
+        assert_eq!(vertices.len(), 36);
+        assert_eq!(mesh.get_primitives().num_primitives(), 12);
+
+        let normals = vertices.get_normals().unwrap();
+        assert_eq!(normals.len(), 36);
+        for (p, n) in vertices.get_positions().iter().zip(normals.iter()) {
+            let side = n.0.iamax();
+            assert_eq!(n.0[side].abs(), 1f32);
+            assert_eq!(n.0.norm(), 1f32);
+            assert_eq!(p.0[side], n.0[side] * 0.5f32);
+        }
+
+        // recompute generates the same smooth normals for the cube
+        let mesh = load_cube_with_normals_mode(NormalsMode::Recompute);
+        check_smooth_cube_normals(&mesh);
+
+        // authored normals are kept by smooth, but replaced by recompute
+        let mut vertices = mesh.get_vertices().clone();
+        vertices
+            .set_normals(vec![Point3D::new(0f32, 0f32, 1f32); 8])
+            .unwrap();
+        let authored = Mesh::new(vertices, mesh.get_primitives().clone()).unwrap();
+
+        let smooth = NormalsMode::Smooth.apply(&authored).unwrap();
+        assert_eq!(
+            smooth.get_vertices().get_normals(),
+            authored.get_vertices().get_normals()
+        );
+
+        let recomputed = NormalsMode::Recompute.apply(&authored).unwrap();
+        check_smooth_cube_normals(&recomputed);
+    }
 }
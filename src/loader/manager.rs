@@ -1,9 +1,18 @@
 use std::{
     collections::{BinaryHeap, HashMap, BTreeSet},
+    path::Path,
     rc::Rc,
 };
 
-use super::{loader::Loader, loader_gltf::LoaderGLTF, loader_off::LoaderOff, ExtensionMap};
+use crate::{structure::CADData, Error};
+
+use super::{
+    loader::Loader,
+    loader_gltf::LoaderGLTF,
+    loader_off::LoaderOff,
+    normalize::{normalize, NormalizeOptions},
+    ExtensionMap,
+};
 
 #[derive(Clone)]
 struct LoaderEntry {
@@ -157,13 +166,53 @@ impl Manager {
     pub fn get_loader_list(&self) -> &[Rc<dyn Loader>] {
         &self.loader
     }
+
+    /// Loads the file from the given path and applies the post-processing steps defined by the
+    /// given options, e.g., baking the transformations, welding duplicate vertices and
+    /// generating missing normals. The loader is determined by the extension of the file.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the file to load.
+    /// * `options` - The options that define the post-processing steps.
+    pub fn load_file_normalized(
+        &self,
+        path: &Path,
+        options: &NormalizeOptions,
+    ) -> Result<CADData, Error> {
+        let ext = match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) => ext,
+            None => {
+                return Err(Error::InvalidArgument(format!(
+                    "Cannot determine the extension of {:?}",
+                    path
+                )));
+            }
+        };
+
+        for mime_type in self.get_mime_types_for_extension(ext) {
+            if let Some(loader) = self.get_loader_by_mime_type(&mime_type) {
+                let cad_data = loader.read_file(path, &mime_type)?;
+                return normalize(&cad_data, options);
+            }
+        }
+
+        Err(Error::InvalidArgument(format!(
+            "Cannot find a loader for {:?}",
+            path
+        )))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
 
-    use crate::{loader::Resource, structure::CADData, Error};
+    use nalgebra_glm::{mat4_to_mat3, Mat4};
+
+    use crate::{
+        loader::{FileResource, NormalsMode, Resource},
+        structure::{Node, Shape},
+    };
 
     use super::*;
 
@@ -294,4 +343,117 @@ mod tests {
             }
         }
     }
+
+    /// Checks recursively that all nodes have identity transformations.
+    fn check_identity_transforms(node: &Node) {
+        assert_eq!(node.get_transform().unwrap_or(Mat4::identity()), Mat4::identity());
+
+        for child in node.get_children() {
+            check_identity_transforms(child);
+        }
+    }
+
+    /// Returns the first shape and its accumulated transformation found in the given subtree.
+    fn find_shape(node: &Node, parent_transform: Mat4) -> Option<(Rc<Shape>, Mat4)> {
+        let transform = parent_transform * node.get_transform().unwrap_or(Mat4::identity());
+        if let Some(shape) = node.get_shapes().first() {
+            return Some((shape.clone(), transform));
+        }
+
+        node.get_children()
+            .iter()
+            .find_map(|child| find_shape(child, transform))
+    }
+
+    #[test]
+    fn test_load_file_normalized_bakes_transforms() {
+        let manager = Manager::new();
+        let path = Path::new("src/loader/test_data/gltf/Box.gltf");
+
+        let cad_data = manager
+            .load_file_normalized(path, &NormalizeOptions::default())
+            .unwrap();
+        check_identity_transforms(cad_data.get_root_node());
+
+        // compare the baked mesh with the transformed mesh of the original data
+        let original = LoaderGLTF::new()
+            .read(&FileResource::new(path.to_owned(), "model/gltf+json"))
+            .unwrap();
+        let (original_shape, transform) =
+            find_shape(original.get_root_node(), Mat4::identity()).unwrap();
+        let (shape, _) = find_shape(cad_data.get_root_node(), Mat4::identity()).unwrap();
+
+        let original_mesh = original_shape.get_parts()[0].get_mesh();
+        let mesh = shape.get_parts()[0].get_mesh();
+
+        // the vertices of the box have distinct normals and must not be welded
+        assert_eq!(mesh.get_vertices().len(), 24);
+        assert_eq!(mesh.get_primitives().num_primitives(), 12);
+
+        let original_vertices = original_mesh.get_vertices();
+        let vertices = mesh.get_vertices();
+        let rotation = mat4_to_mat3(&transform);
+        for i in 0..vertices.len() {
+            let p = original_vertices.get_positions()[i].0;
+            let n = original_vertices.get_normals().unwrap()[i].0;
+            let expected_p = (transform * p.push(1f32)).xyz();
+            let expected_n = rotation * n;
+
+            assert!((vertices.get_positions()[i].0 - expected_p).norm() < 1e-6f32);
+            assert!((vertices.get_normals().unwrap()[i].0 - expected_n).norm() < 1e-6f32);
+        }
+    }
+
+    #[test]
+    fn test_load_file_normalized_welds_and_generates_normals() {
+        let manager = Manager::new();
+        let path = Path::new("src/loader/test_data/cube_split.off");
+
+        let cad_data = manager
+            .load_file_normalized(path, &NormalizeOptions::default())
+            .unwrap();
+        check_identity_transforms(cad_data.get_root_node());
+
+        let (shape, _) = find_shape(cad_data.get_root_node(), Mat4::identity()).unwrap();
+        let mesh = shape.get_parts()[0].get_mesh();
+
+        // the 24 vertices of the faces are welded to the 8 corners of the cube
+        let vertices = mesh.get_vertices();
+        assert_eq!(vertices.len(), 8);
+        assert_eq!(mesh.get_primitives().num_primitives(), 12);
+        assert!(mesh.is_outward_oriented());
+
+        // the generated smooth normals point outwards
+        let normals = vertices.get_normals().unwrap();
+        assert_eq!(normals.len(), 8);
+        for (n, p) in normals.iter().zip(vertices.get_positions()) {
+            assert!((n.0.norm() - 1f32).abs() < 1e-5f32);
+            assert!(n.0.dot(&p.0) > 0f32);
+        }
+
+        // without any post-processing the data remains untouched
+        let options = NormalizeOptions {
+            bake_transforms: false,
+            weld_vertices: false,
+            weld_epsilon: 0f32,
+            normals_mode: NormalsMode::Keep,
+        };
+        let cad_data = manager.load_file_normalized(path, &options).unwrap();
+        let (shape, _) = find_shape(cad_data.get_root_node(), Mat4::identity()).unwrap();
+        let mesh = shape.get_parts()[0].get_mesh();
+        assert_eq!(mesh.get_vertices().len(), 24);
+        assert!(mesh.get_vertices().get_normals().is_none());
+    }
+
+    #[test]
+    fn test_load_file_normalized_unknown_extension() {
+        let manager = Manager::new();
+
+        assert!(manager
+            .load_file_normalized(Path::new("foobar.unknown"), &NormalizeOptions::default())
+            .is_err());
+        assert!(manager
+            .load_file_normalized(Path::new("foobar"), &NormalizeOptions::default())
+            .is_err());
+    }
 }
@@ -0,0 +1,126 @@
+use std::rc::Rc;
+
+use crate::{
+    basic_types::{Color, RGB},
+    structure::{Material, PhongMaterialData},
+};
+
+/// The reason why a loader cannot resolve the material of a part on its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MaterialFallbackReason {
+    /// The format doesn't specify any materials at all, e.g., OFF.
+    Unspecified,
+
+    /// The part doesn't reference any material and the default material of the format is used.
+    NoMaterial,
+
+    /// The part references a material with the given index which cannot be found.
+    MissingIndex(usize),
+}
+
+/// The context of a part for which the loader requests a fallback material.
+#[derive(Clone, Debug)]
+pub struct MaterialRequest<'a> {
+    /// The label of the node the part is attached to.
+    pub node_label: &'a str,
+
+    /// The label of the mesh the part belongs to. Is none if the format doesn't define meshes.
+    pub mesh_label: Option<&'a str>,
+
+    /// The reason why the loader requests a fallback material.
+    pub reason: MaterialFallbackReason,
+}
+
+/// The material resolver is consulted by the loaders whenever the material of a part cannot be
+/// resolved from the loaded data.
+///
+/// Loaders share a shape between several nodes only if the resolver returns the same materials
+/// for them. Resolvers should therefore hand out clones of shared `Rc`s instead of allocating a
+/// new material for each request, which would give every node its own copy of the shape.
+pub trait MaterialResolver {
+    /// Returns the fallback material for the part described by the given request.
+    ///
+    /// # Arguments
+    /// * `request` - The context of the part for which a material is needed.
+    fn resolve(&self, request: &MaterialRequest) -> Rc<Material>;
+}
+
+/// The default material resolver. Parts of formats without materials get no material and all
+/// other parts get a shared black phong material.
+pub struct DefaultMaterialResolver {
+    none_material: Rc<Material>,
+    default_material: Rc<Material>,
+}
+
+impl DefaultMaterialResolver {
+    /// Returns a new default material resolver.
+    pub fn new() -> Self {
+        let phong_data = PhongMaterialData {
+            diffuse_color: RGB::black(),
+            ..Default::default()
+        };
+
+        Self {
+            none_material: Rc::new(Material::None),
+            default_material: Rc::new(Material::PhongMaterial(phong_data)),
+        }
+    }
+}
+
+impl Default for DefaultMaterialResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MaterialResolver for DefaultMaterialResolver {
+    fn resolve(&self, request: &MaterialRequest) -> Rc<Material> {
+        match request.reason {
+            MaterialFallbackReason::Unspecified => self.none_material.clone(),
+            MaterialFallbackReason::NoMaterial | MaterialFallbackReason::MissingIndex(_) => {
+                self.default_material.clone()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_material_resolver() {
+        let resolver = DefaultMaterialResolver::new();
+
+        let request = MaterialRequest {
+            node_label: "root",
+            mesh_label: None,
+            reason: MaterialFallbackReason::Unspecified,
+        };
+        let m = resolver.resolve(&request);
+        assert!(matches!(m.as_ref(), Material::None));
+
+        let request = MaterialRequest {
+            node_label: "node",
+            mesh_label: Some("mesh"),
+            reason: MaterialFallbackReason::NoMaterial,
+        };
+        let m0 = resolver.resolve(&request);
+
+        let request = MaterialRequest {
+            node_label: "node",
+            mesh_label: Some("mesh"),
+            reason: MaterialFallbackReason::MissingIndex(42),
+        };
+        let m1 = resolver.resolve(&request);
+
+        // both fallbacks share the same black phong material
+        assert!(Rc::ptr_eq(&m0, &m1));
+        match m0.as_ref() {
+            Material::PhongMaterial(phong_data) => {
+                assert_eq!(phong_data.diffuse_color.0, RGB::black().0);
+            }
+            Material::None => panic!("Expected phong material"),
+        }
+    }
+}
@@ -4,8 +4,18 @@ pub mod loader_off;
 
 mod loader;
 mod manager;
+mod material_resolver;
+mod normalize;
+mod normals_mode;
+mod report;
 mod resource;
 
 pub use loader::{Loader, ExtensionMap};
 pub use manager::Manager;
+pub use material_resolver::{
+    DefaultMaterialResolver, MaterialFallbackReason, MaterialRequest, MaterialResolver,
+};
+pub use normalize::NormalizeOptions;
+pub use normals_mode::NormalsMode;
+pub use report::{LoadReport, PrimitiveFailure};
 pub use resource::*;
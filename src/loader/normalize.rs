@@ -0,0 +1,198 @@
+use std::{collections::HashMap, rc::Rc};
+
+use nalgebra_glm::Mat4;
+
+use crate::{
+    structure::{CADData, Mesh, Node, Shape, ShapePart},
+    Error, ID,
+};
+
+use super::NormalsMode;
+
+/// The options for the post-processing steps applied by the loader manager to create a clean
+/// renderable set of meshes.
+#[derive(Clone, Debug)]
+pub struct NormalizeOptions {
+    /// If true, the transformations of the nodes are baked into the meshes and all nodes have
+    /// identity transformations.
+    pub bake_transforms: bool,
+
+    /// If true, duplicate vertices are merged into a single vertex.
+    pub weld_vertices: bool,
+
+    /// The cell size of the grid used for comparing the vertex positions while welding.
+    pub weld_epsilon: f32,
+
+    /// Defines how the normals of the meshes are handled after welding.
+    pub normals_mode: NormalsMode,
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        Self {
+            bake_transforms: true,
+            weld_vertices: true,
+            weld_epsilon: 1e-5f32,
+            normals_mode: NormalsMode::Smooth,
+        }
+    }
+}
+
+/// Returns a normalized copy of the given CAD data, i.e., all post-processing steps enabled in
+/// the given options are applied to the meshes of the CAD data.
+///
+/// # Arguments
+/// * `cad_data` - The CAD data to normalize.
+/// * `options` - The options that define the post-processing steps.
+pub fn normalize(cad_data: &CADData, options: &NormalizeOptions) -> Result<CADData, Error> {
+    let root_node = map_meshes(
+        cad_data.get_root_node(),
+        options.bake_transforms,
+        |mesh, transform| normalize_mesh(mesh, transform, options),
+    )?;
+
+    Ok(CADData::new(root_node))
+}
+
+/// Returns a copy of the given node and its children where the mesh of each shape part is
+/// replaced by the mesh returned by the given function.
+///
+/// If the transformations are baked, the function gets the world transformation of the node,
+/// every shape instance gets its own copy and all nodes have identity transformations.
+/// Otherwise, the function gets no transformation, the node transformations are kept and shared
+/// shapes remain shared.
+///
+/// # Arguments
+/// * `root_node` - The node to copy.
+/// * `bake_transforms` - If true, the transformations of the nodes are passed to the function.
+/// * `map_mesh` - The function that returns the new mesh for the given mesh and transformation.
+pub fn map_meshes<F>(
+    root_node: &Node,
+    bake_transforms: bool,
+    mut map_mesh: F,
+) -> Result<Node, Error>
+where
+    F: FnMut(&Mesh, Option<&Mat4>) -> Result<Mesh, Error>,
+{
+    let mut shape_map = HashMap::new();
+
+    map_node_meshes(
+        root_node,
+        &Mat4::identity(),
+        bake_transforms,
+        &mut map_mesh,
+        &mut shape_map,
+    )
+}
+
+/// Returns a copy of the given node and its children with the meshes replaced by the given
+/// function.
+///
+/// # Arguments
+/// * `node` - The node to copy.
+/// * `parent_transform` - The accumulated transformation of all parent nodes.
+/// * `bake_transforms` - If true, the transformations of the nodes are passed to the function.
+/// * `map_mesh` - The function that returns the new mesh for the given mesh and transformation.
+/// * `shape_map` - Map of already copied shapes to keep shared shapes shared.
+fn map_node_meshes<F>(
+    node: &Node,
+    parent_transform: &Mat4,
+    bake_transforms: bool,
+    map_mesh: &mut F,
+    shape_map: &mut HashMap<ID, Rc<Shape>>,
+) -> Result<Node, Error>
+where
+    F: FnMut(&Mesh, Option<&Mat4>) -> Result<Mesh, Error>,
+{
+    let mut out_node = Node::new(node.get_label().to_owned());
+
+    let world_transform = match node.get_transform() {
+        Some(t) => parent_transform * t,
+        None => *parent_transform,
+    };
+
+    for shape in node.get_shapes() {
+        let out_shape = if bake_transforms {
+            Rc::new(map_shape_meshes(shape, Some(&world_transform), map_mesh)?)
+        } else {
+            match shape_map.get(&shape.get_id()) {
+                Some(s) => s.clone(),
+                None => {
+                    let s = Rc::new(map_shape_meshes(shape, None, map_mesh)?);
+                    shape_map.insert(shape.get_id(), s.clone());
+                    s
+                }
+            }
+        };
+
+        out_node.attach_shape(out_shape);
+    }
+
+    if !bake_transforms {
+        if let Some(t) = node.get_transform() {
+            out_node.set_transform(t);
+        }
+    }
+
+    for child in node.get_children() {
+        let out_child = map_node_meshes(
+            child,
+            &world_transform,
+            bake_transforms,
+            map_mesh,
+            shape_map,
+        )?;
+        out_node.add_child(out_child);
+    }
+
+    Ok(out_node)
+}
+
+/// Returns a copy of the given shape with the meshes replaced by the given function.
+///
+/// # Arguments
+/// * `shape` - The shape to copy.
+/// * `transform` - The optional transformation passed to the function.
+/// * `map_mesh` - The function that returns the new mesh for the given mesh and transformation.
+fn map_shape_meshes<F>(
+    shape: &Shape,
+    transform: Option<&Mat4>,
+    map_mesh: &mut F,
+) -> Result<Shape, Error>
+where
+    F: FnMut(&Mesh, Option<&Mat4>) -> Result<Mesh, Error>,
+{
+    let mut out_shape = Shape::new();
+
+    for part in shape.get_parts() {
+        let mesh = map_mesh(&part.get_mesh(), transform)?;
+        out_shape.add_part(ShapePart::new(Rc::new(mesh), part.get_material()));
+    }
+
+    Ok(out_shape)
+}
+
+/// Returns a normalized copy of the given mesh. Morph targets are resolved by using the static
+/// pose of the mesh.
+///
+/// # Arguments
+/// * `mesh` - The mesh to normalize.
+/// * `transform` - The optional transformation to bake into the mesh.
+/// * `options` - The options that define the post-processing steps.
+fn normalize_mesh(
+    mesh: &Mesh,
+    transform: Option<&Mat4>,
+    options: &NormalizeOptions,
+) -> Result<Mesh, Error> {
+    // the post-processing steps are applied onto the static pose of the mesh
+    let mut mesh = mesh.to_static_pose()?;
+    if let Some(t) = transform {
+        mesh = mesh.transform(t)?;
+    }
+
+    if options.weld_vertices {
+        mesh = mesh.weld_vertices(options.weld_epsilon)?;
+    }
+
+    options.normals_mode.apply(&mesh)
+}
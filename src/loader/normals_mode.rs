@@ -0,0 +1,72 @@
+use crate::{
+    structure::{CADData, Mesh},
+    Error,
+};
+
+use super::normalize::map_meshes;
+
+/// Defines how the normals of the loaded meshes are handled after the geometry has been read.
+/// Primitives which are not triangle based, e.g., points, are never changed. Whenever normals are
+/// generated, the normal displacements of the morph targets are dropped, as they refer to the
+/// replaced normals.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NormalsMode {
+    /// The authored normals are preserved and no normals are generated.
+    #[default]
+    Keep,
+
+    /// Area weighted smooth normals are generated for all meshes without normals.
+    Smooth,
+
+    /// Each triangle gets its own vertices with the normal of the triangle.
+    Flat,
+
+    /// Area weighted smooth normals are generated for all meshes, replacing authored normals.
+    Recompute,
+}
+
+impl NormalsMode {
+    /// Returns a copy of the given mesh with the normals handled according to the mode.
+    ///
+    /// # Arguments
+    /// * `mesh` - The mesh whose normals are handled.
+    pub fn apply(&self, mesh: &Mesh) -> Result<Mesh, Error> {
+        if mesh.get_primitives().to_triangles().is_empty() {
+            return Ok(mesh.clone());
+        }
+
+        match self {
+            NormalsMode::Keep => Ok(mesh.clone()),
+            NormalsMode::Smooth if mesh.get_vertices().get_normals().is_some() => Ok(mesh.clone()),
+            NormalsMode::Smooth | NormalsMode::Recompute => {
+                let mut vertices = mesh.get_vertices().clone();
+                vertices.set_normals(mesh.compute_smooth_normals())?;
+
+                // the normal displacements of the morph targets don't match the new normals
+                let mut out_mesh = Mesh::new(vertices, mesh.get_primitives().clone())?;
+                if let Some(morph_targets) = mesh.get_morph_targets() {
+                    out_mesh.set_morph_targets(morph_targets.without_normal_deltas())?;
+                }
+
+                Ok(out_mesh)
+            }
+            NormalsMode::Flat => mesh.to_flat_shaded(),
+        }
+    }
+}
+
+/// Returns the given CAD data with the normals of all meshes handled according to the given
+/// mode. Shared shapes remain shared.
+///
+/// # Arguments
+/// * `cad_data` - The CAD data whose normals are handled.
+/// * `mode` - The mode that defines how the normals are handled.
+pub fn apply_normals_mode(cad_data: CADData, mode: NormalsMode) -> Result<CADData, Error> {
+    if mode == NormalsMode::Keep {
+        return Ok(cad_data);
+    }
+
+    let root_node = map_meshes(cad_data.get_root_node(), false, |mesh, _| mode.apply(mesh))?;
+
+    Ok(CADData::new(root_node))
+}
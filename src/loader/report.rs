@@ -0,0 +1,53 @@
+use crate::{structure::PrimitiveType, Error};
+
+/// A primitive with invalid geometry, e.g., invalid indices, which has been dropped by the loader.
+#[derive(Clone, Debug)]
+pub struct PrimitiveFailure {
+    /// The type of the dropped primitive.
+    pub primitive_type: PrimitiveType,
+
+    /// The path of the primitive within the meshes of the file, i.e.,
+    /// "<mesh-index>/<primitive-index>". Meshes are identified by their index, as their names are
+    /// neither unique nor required. The nodes are not part of the path, as several nodes can
+    /// instantiate the same mesh.
+    pub mesh_path: String,
+
+    /// The name of the mesh the primitive belongs to, if any.
+    pub mesh_name: Option<String>,
+
+    /// The error that occurred while reading the primitive.
+    pub error: Error,
+}
+
+/// The load report collects all non-fatal problems that occurred while loading CAD data.
+#[derive(Clone, Debug, Default)]
+pub struct LoadReport {
+    primitive_failures: Vec<PrimitiveFailure>,
+}
+
+impl LoadReport {
+    /// Returns a new empty load report.
+    pub fn new() -> Self {
+        Self {
+            primitive_failures: Vec::new(),
+        }
+    }
+
+    /// Returns true if no problems have been reported.
+    pub fn is_empty(&self) -> bool {
+        self.primitive_failures.is_empty()
+    }
+
+    /// Adds a dropped primitive to the report.
+    ///
+    /// # Arguments
+    /// * `failure` - The description of the failed primitive.
+    pub fn add_primitive_failure(&mut self, failure: PrimitiveFailure) {
+        self.primitive_failures.push(failure);
+    }
+
+    /// Returns a reference onto all dropped primitives.
+    pub fn get_primitive_failures(&self) -> &[PrimitiveFailure] {
+        &self.primitive_failures
+    }
+}
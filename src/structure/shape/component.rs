@@ -1,6 +1,6 @@
 use std::fmt::Debug;
 
-use nalgebra_glm::Vec3;
+use nalgebra_glm::{Vec2, Vec3};
 
 /// The trait for components inside attributes.
 pub trait Component: Sized + Default + Clone + Copy + PartialEq + Debug {
@@ -53,6 +53,33 @@ impl Component for Point3D {
 /// A single normal.
 pub type Normal = Point3D;
 
+/// A single point in 2D.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point2D(pub Vec2);
+
+impl Point2D {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self(Vec2::new(x, y))
+    }
+}
+
+impl Default for Point2D {
+    #[inline]
+    fn default() -> Self {
+        Self(Vec2::new(0f32, 0f32))
+    }
+}
+
+impl Component for Point2D {
+    #[inline]
+    fn interpolate(&self, rhs: &Self, f: f32) -> Self {
+        Self(self.0 * (1f32 - f) + rhs.0 * f)
+    }
+}
+
+/// A single texture coordinate.
+pub type TexCoord = Point2D;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -76,4 +103,14 @@ mod tests {
         assert_eq!(a.interpolate(&b, 1f32), b);
         assert_eq!(a.interpolate(&b, 0.5f32), Point3D::new(0f32, 0f32, 0f32));
     }
+
+    #[test]
+    fn test_point2d_interpolate() {
+        let a: Point2D = Point2D::new(-1f32, -4f32);
+        let b: Point2D = Point2D::new(1f32, 4f32);
+
+        assert_eq!(a.interpolate(&b, 0f32), a);
+        assert_eq!(a.interpolate(&b, 1f32), b);
+        assert_eq!(a.interpolate(&b, 0.5f32), Point2D::new(0f32, 0f32));
+    }
 }
@@ -11,6 +11,16 @@ impl Default for Material {
     }
 }
 
+/// A reference onto a texture image used by a material.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextureReference {
+    /// The uri of the texture image. Is none if the image is embedded into the loaded data.
+    pub uri: Option<String>,
+
+    /// The index of the texture coordinate channel of the vertices used for the texture.
+    pub tex_coord_channel: usize,
+}
+
 pub struct PhongMaterialData {
     /// The transparency field specifies how "clear" an object is, with 1.0 being completely
     /// transparent, and 0.0 completely opaque.
@@ -40,6 +50,18 @@ pub struct PhongMaterialData {
     /// of light sources, not their positions with respect to the surface. Ambient color is
     /// calculated as ambientIntensity × diffuse color.
     pub ambient_intensity: f32,
+
+    /// The optional texture which modulates the diffuse color.
+    pub diffuse_texture: Option<TextureReference>,
+
+    /// The optional texture which modulates the emissive color.
+    pub emissive_texture: Option<TextureReference>,
+
+    /// The optional tangent space normal map.
+    pub normal_texture: Option<TextureReference>,
+
+    /// The optional texture which defines the ambient occlusion, e.g., a precomputed lightmap.
+    pub occlusion_texture: Option<TextureReference>,
 }
 
 impl Default for PhongMaterialData {
@@ -51,6 +73,10 @@ impl Default for PhongMaterialData {
             shininess: 0.2,
             specular_color: RGB::black(),
             transparency: 0f32,
+            diffuse_texture: None,
+            emissive_texture: None,
+            normal_texture: None,
+            occlusion_texture: None,
         }
     }
 }
@@ -1,11 +1,22 @@
-use crate::error::Error;
+use std::{borrow::Cow, collections::HashMap};
 
-use super::{primitives::Primitives, vertices::Vertices};
+use nalgebra_glm::{cross, determinant, dot, inverse_transpose, mat4_to_mat3, Mat4, Vec3, Vec4};
+
+use crate::{basic_types::RGBA, error::Error};
+
+use super::{
+    component::Point3D,
+    morph_targets::MorphTargets,
+    primitives::{IndexData, PrimitiveType, Primitives},
+    vertices::{Normals, Vertices},
+};
 
 /// A mesh is a tessellated geometry consisting of vertices and primitives.
+#[derive(Clone)]
 pub struct Mesh {
     vertices: Vertices,
     primitives: Primitives,
+    morph_targets: Option<MorphTargets>,
 }
 
 impl Mesh {
@@ -31,6 +42,7 @@ impl Mesh {
         Ok(Self {
             vertices,
             primitives,
+            morph_targets: None,
         })
     }
 
@@ -43,4 +55,701 @@ impl Mesh {
     pub fn get_primitives(&self) -> &Primitives {
         &self.primitives
     }
+
+    /// Sets the morph targets of the mesh. If the number of displacements of any target does not
+    /// match the number of vertices, an error is returned.
+    ///
+    /// # Arguments
+    /// * `morph_targets` - The morph targets to set.
+    pub fn set_morph_targets(&mut self, morph_targets: MorphTargets) -> Result<(), Error> {
+        let num_vertices = self.vertices.len();
+
+        for (i, target) in morph_targets.get_targets().iter().enumerate() {
+            let num_position_deltas = target.get_position_deltas().map(|d| d.len());
+            let num_normal_deltas = target.get_normal_deltas().map(|d| d.len());
+
+            for n in [num_position_deltas, num_normal_deltas].iter().flatten() {
+                if *n != num_vertices {
+                    return Err(Error::InvalidArgument(format!(
+                        "Got {} vertices, but morph target {} has {} displacements",
+                        num_vertices, i, n
+                    )));
+                }
+            }
+        }
+
+        self.morph_targets = Some(morph_targets);
+        Ok(())
+    }
+
+    /// Returns a reference onto the morph targets of the mesh.
+    pub fn get_morph_targets(&self) -> Option<&MorphTargets> {
+        self.morph_targets.as_ref()
+    }
+
+    /// Returns a copy of the mesh where the morph targets are applied with the given weights,
+    /// i.e., the weighted displacements are added to the base vertices. The returned mesh has no
+    /// morph targets. If the number of weights does not match the number of morph targets, an
+    /// error is returned.
+    ///
+    /// # Arguments
+    /// * `weights` - The weight for each morph target.
+    pub fn apply_morph_targets(&self, weights: &[f32]) -> Result<Mesh, Error> {
+        let targets = match &self.morph_targets {
+            Some(morph_targets) => morph_targets.get_targets(),
+            None => &[],
+        };
+
+        if targets.len() != weights.len() {
+            return Err(Error::InvalidArgument(format!(
+                "Got {} morph targets, but {} weights",
+                targets.len(),
+                weights.len()
+            )));
+        }
+
+        let mut positions: Vec<Vec3> = self.vertices.get_positions().iter().map(|p| p.0).collect();
+        let mut normals: Option<Vec<Vec3>> = self
+            .vertices
+            .get_normals()
+            .map(|normals| normals.iter().map(|n| n.0).collect());
+
+        for (target, weight) in targets.iter().zip(weights.iter()) {
+            if *weight == 0f32 {
+                continue;
+            }
+
+            if let Some(deltas) = target.get_position_deltas() {
+                for (p, d) in positions.iter_mut().zip(deltas.iter()) {
+                    *p += d.0 * *weight;
+                }
+            }
+
+            if let (Some(normals), Some(deltas)) = (normals.as_mut(), target.get_normal_deltas()) {
+                for (n, d) in normals.iter_mut().zip(deltas.iter()) {
+                    *n += d.0 * *weight;
+                }
+            }
+        }
+
+        let mut vertices = self.vertices.clone();
+        vertices.set_positions(positions.into_iter().map(Point3D).collect())?;
+        if let Some(normals) = normals {
+            vertices.set_normals(normals.iter().map(normalize_or_zero).collect())?;
+        }
+
+        Mesh::new(vertices, self.primitives.clone())
+    }
+
+    /// Returns a copy of the mesh in its static pose, i.e., the morph targets are applied with
+    /// their weights. If the mesh has no morph targets or all weights are zero, the base mesh is
+    /// returned. The returned mesh has no morph targets.
+    pub fn to_static_pose(&self) -> Result<Mesh, Error> {
+        match &self.morph_targets {
+            Some(morph_targets) if !morph_targets.has_zero_weights() => {
+                self.apply_morph_targets(morph_targets.get_weights())
+            }
+            _ => Mesh::new(self.vertices.clone(), self.primitives.clone()),
+        }
+    }
+
+    /// Returns the vertices of the mesh in its static pose. The base vertices are borrowed if
+    /// the mesh has no morph targets or all weights are zero.
+    fn get_static_pose_vertices(&self) -> Result<Cow<'_, Vertices>, Error> {
+        match &self.morph_targets {
+            Some(morph_targets) if !morph_targets.has_zero_weights() => {
+                let mesh = self.apply_morph_targets(morph_targets.get_weights())?;
+                Ok(Cow::Owned(mesh.vertices))
+            }
+            _ => Ok(Cow::Borrowed(&self.vertices)),
+        }
+    }
+
+    /// Returns true if the triangles of the mesh are oriented outwards, i.e., their normals
+    /// point away from the enclosed volume. The check computes the signed volume of the mesh
+    /// w.r.t. the centroid of the vertices using the divergence theorem. Using the centroid
+    /// instead of the origin keeps the result stable for meshes translated far from the origin.
+    /// The mesh is checked in its static pose, i.e., with the morph target weights applied.
+    /// The result is only meaningful for closed meshes. If the mesh has no triangles, false is
+    /// returned.
+    pub fn is_outward_oriented(&self) -> bool {
+        let triangles = self.primitives.to_triangles();
+        let vertices = match self.get_static_pose_vertices() {
+            Ok(vertices) => vertices,
+            Err(_) => return false,
+        };
+
+        let positions = vertices.get_positions();
+        if triangles.is_empty() || positions.is_empty() {
+            return false;
+        }
+
+        let centroid =
+            positions.iter().fold(Vec3::zeros(), |sum, p| sum + p.0) / positions.len() as f32;
+
+        let volume: f32 = triangles
+            .iter()
+            .map(|t| {
+                let v0 = positions[t[0] as usize].0 - centroid;
+                let v1 = positions[t[1] as usize].0 - centroid;
+                let v2 = positions[t[2] as usize].0 - centroid;
+
+                dot(&v0, &cross(&v1, &v2))
+            })
+            .sum::<f32>()
+            / 6f32;
+
+        volume > 0f32
+    }
+
+    /// Returns a downsampled point cloud of the mesh. The space is partitioned into a grid of
+    /// cubic voxels and all vertices inside a voxel are replaced by a single representative
+    /// point at their centroid. Colors and normals are averaged, whereby the normals are
+    /// normalized again. Other attributes like texture coordinates and morph targets are dropped.
+    /// All vertices of the mesh are treated as points, independent of the primitive type. The
+    /// mesh is downsampled in its static pose, i.e., with the morph target weights applied.
+    ///
+    /// If the voxel size is not greater than zero, an error is returned.
+    ///
+    /// # Arguments
+    /// * `voxel_size` - The edge length of a single voxel. Must be greater than zero.
+    pub fn voxel_downsample(&self, voxel_size: f32) -> Result<Mesh, Error> {
+        if voxel_size.is_nan() || voxel_size <= 0f32 {
+            return Err(Error::InvalidArgument(format!(
+                "Voxel size must be greater than zero, but got {}",
+                voxel_size
+            )));
+        }
+
+        let vertices = self.get_static_pose_vertices()?;
+        let positions = vertices.get_positions();
+        let normals = vertices.get_normals();
+        let colors = vertices.get_colors();
+
+        // the spatial hash grid maps each occupied voxel to its index in the accumulators
+        let mut grid: HashMap<(i64, i64, i64), usize> = HashMap::new();
+        let mut counts: Vec<usize> = Vec::new();
+        let mut position_sums: Vec<Vec3> = Vec::new();
+        let mut normal_sums: Vec<Vec3> = Vec::new();
+        let mut color_sums: Vec<Vec4> = Vec::new();
+
+        for (i, p) in positions.iter().enumerate() {
+            let key = (
+                (p.0.x / voxel_size).floor() as i64,
+                (p.0.y / voxel_size).floor() as i64,
+                (p.0.z / voxel_size).floor() as i64,
+            );
+
+            let voxel_index = *grid.entry(key).or_insert_with(|| {
+                counts.push(0);
+                position_sums.push(Vec3::zeros());
+                normal_sums.push(Vec3::zeros());
+                color_sums.push(Vec4::zeros());
+
+                counts.len() - 1
+            });
+
+            counts[voxel_index] += 1;
+            position_sums[voxel_index] += p.0;
+
+            if let Some(normals) = normals {
+                normal_sums[voxel_index] += normals[i].0;
+            }
+
+            if let Some(colors) = colors {
+                color_sums[voxel_index] += colors[i].0;
+            }
+        }
+
+        let num_points = counts.len();
+
+        let out_positions = position_sums
+            .iter()
+            .zip(counts.iter())
+            .map(|(p, n)| Point3D(p / *n as f32))
+            .collect();
+        let mut out_vertices = Vertices::from_positions(out_positions);
+
+        if normals.is_some() {
+            let out_normals = normal_sums.iter().map(normalize_or_zero).collect();
+
+            out_vertices.set_normals(out_normals)?;
+        }
+
+        if colors.is_some() {
+            let out_colors = color_sums
+                .iter()
+                .zip(counts.iter())
+                .map(|(c, n)| RGBA(c / *n as f32))
+                .collect();
+
+            out_vertices.set_colors(out_colors)?;
+        }
+
+        let out_primitives =
+            Primitives::new(IndexData::NonIndexed(num_points), PrimitiveType::Point)?;
+
+        Mesh::new(out_vertices, out_primitives)
+    }
+
+    /// Returns a copy of the mesh with all positions and normals transformed by the given
+    /// matrix. Normals are transformed by the inverse transpose of the matrix and normalized.
+    /// If the matrix mirrors the geometry, the winding order of the triangles is flipped to keep
+    /// their orientation, whereby triangle strips and fans are converted to triangles.
+    ///
+    /// # Arguments
+    /// * `m` - The transformation matrix to apply.
+    pub fn transform(&self, m: &Mat4) -> Result<Mesh, Error> {
+        let m3 = mat4_to_mat3(m);
+        let normal_matrix = inverse_transpose(m3);
+
+        let mut vertices = self.vertices.clone();
+        let positions = self
+            .vertices
+            .get_positions()
+            .iter()
+            .map(|p| Point3D((m * p.0.push(1f32)).xyz()))
+            .collect();
+        vertices.set_positions(positions)?;
+
+        if let Some(normals) = self.vertices.get_normals() {
+            let normals = normals
+                .iter()
+                .map(|n| normalize_or_zero(&(normal_matrix * n.0)))
+                .collect();
+            vertices.set_normals(normals)?;
+        }
+
+        let triangles = self.primitives.to_triangles();
+        let primitives = if determinant(&m3) < 0f32 && !triangles.is_empty() {
+            let indices = triangles.iter().flat_map(|t| [t[0], t[2], t[1]]).collect();
+            Primitives::new(IndexData::Indices(indices), PrimitiveType::Triangles)?
+        } else {
+            self.primitives.clone()
+        };
+
+        let mut mesh = Mesh::new(vertices, primitives)?;
+        if let Some(morph_targets) = &self.morph_targets {
+            mesh.set_morph_targets(morph_targets.transform(&m3, &normal_matrix))?;
+        }
+
+        Ok(mesh)
+    }
+
+    /// Returns a copy of the mesh where duplicate vertices are merged into a single vertex.
+    /// Two vertices are duplicates if their positions fall into the same cell of a grid with
+    /// the given cell size and all their other attributes are equal. The resulting primitives
+    /// are always indexed.
+    ///
+    /// # Arguments
+    /// * `epsilon` - The cell size of the grid used for comparing the positions. If it is not
+    ///   greater than zero, the positions must be exactly equal.
+    pub fn weld_vertices(&self, epsilon: f32) -> Result<Mesh, Error> {
+        let num_vertices = self.vertices.len();
+
+        let mut vertex_map: HashMap<Vec<i64>, u32> = HashMap::new();
+        let mut representatives: Vec<u32> = Vec::new();
+        let mut remap: Vec<u32> = Vec::with_capacity(num_vertices);
+
+        for i in 0..num_vertices {
+            let key = self.create_weld_key(i, epsilon);
+            let index = *vertex_map.entry(key).or_insert_with(|| {
+                representatives.push(i as u32);
+                (representatives.len() - 1) as u32
+            });
+
+            remap.push(index);
+        }
+
+        let vertices = self.vertices.select(&representatives);
+        let indices = self
+            .primitives
+            .get_raw_index_data()
+            .to_indices()
+            .iter()
+            .map(|i| remap[*i as usize])
+            .collect();
+        let primitives = Primitives::new(
+            IndexData::Indices(indices),
+            self.primitives.get_primitive_type(),
+        )?;
+
+        let mut mesh = Mesh::new(vertices, primitives)?;
+        if let Some(morph_targets) = &self.morph_targets {
+            mesh.set_morph_targets(morph_targets.select(&representatives))?;
+        }
+
+        Ok(mesh)
+    }
+
+    /// Computes smooth normals for all vertices by accumulating the area weighted normals of all
+    /// adjacent triangles. Vertices which are not part of any triangle get a zero normal.
+    pub fn compute_smooth_normals(&self) -> Normals {
+        let positions = self.vertices.get_positions();
+        let mut normals = vec![Vec3::zeros(); positions.len()];
+
+        for t in self.primitives.to_triangles() {
+            let v0 = positions[t[0] as usize].0;
+            let v1 = positions[t[1] as usize].0;
+            let v2 = positions[t[2] as usize].0;
+
+            // the length of the cross product is twice the area of the triangle
+            let n = cross(&(v1 - v0), &(v2 - v0));
+            for i in t.iter() {
+                normals[*i as usize] += n;
+            }
+        }
+
+        normals.iter().map(normalize_or_zero).collect()
+    }
+
+    /// Returns a copy of the mesh where each triangle has its own three vertices with the normal
+    /// of the triangle, i.e., the mesh is shaded flat. Strips and fans are expanded into a list of
+    /// non-indexed triangles and primitives which are not triangle based are dropped. The morph
+    /// targets keep their position displacements, but lose their normal displacements.
+    pub fn to_flat_shaded(&self) -> Result<Mesh, Error> {
+        let corners: Vec<u32> = self
+            .primitives
+            .to_triangles()
+            .into_iter()
+            .flatten()
+            .collect();
+
+        let mut vertices = self.vertices.select(&corners);
+        let normals: Normals = vertices
+            .get_positions()
+            .chunks(3)
+            .flat_map(|t| {
+                let n = normalize_or_zero(&cross(&(t[1].0 - t[0].0), &(t[2].0 - t[0].0)));
+                [n, n, n]
+            })
+            .collect();
+        vertices.set_normals(normals)?;
+
+        let primitives = Primitives::new(
+            IndexData::NonIndexed(corners.len()),
+            PrimitiveType::Triangles,
+        )?;
+
+        let mut mesh = Mesh::new(vertices, primitives)?;
+        if let Some(morph_targets) = &self.morph_targets {
+            mesh.set_morph_targets(morph_targets.select(&corners).without_normal_deltas())?;
+        }
+
+        Ok(mesh)
+    }
+
+    /// Creates the key for comparing the specified vertex with other vertices when welding.
+    ///
+    /// # Arguments
+    /// * `index` - The index of the vertex.
+    /// * `epsilon` - The cell size of the grid used for comparing the positions.
+    fn create_weld_key(&self, index: usize, epsilon: f32) -> Vec<i64> {
+        let mut key = Vec::new();
+
+        let p = self.vertices.get_positions()[index].0;
+        if epsilon > 0f32 {
+            key.extend(p.iter().map(|c| (c / epsilon).round() as i64));
+        } else {
+            key.extend(p.iter().map(|c| c.to_bits() as i64));
+        }
+
+        if let Some(normals) = self.vertices.get_normals() {
+            key.extend(normals[index].0.iter().map(|c| c.to_bits() as i64));
+        }
+
+        if let Some(colors) = self.vertices.get_colors() {
+            key.extend(colors[index].0.iter().map(|c| c.to_bits() as i64));
+        }
+
+        for channel in 0..self.vertices.num_tex_coord_channels() {
+            if let Some(tex_coords) = self.vertices.get_tex_coords(channel) {
+                key.extend(tex_coords[index].0.iter().map(|c| c.to_bits() as i64));
+            }
+        }
+
+        if let Some(morph_targets) = &self.morph_targets {
+            for target in morph_targets.get_targets() {
+                for deltas in [target.get_position_deltas(), target.get_normal_deltas()]
+                    .iter()
+                    .flatten()
+                {
+                    key.extend(deltas[index].0.iter().map(|c| c.to_bits() as i64));
+                }
+            }
+        }
+
+        key
+    }
+}
+
+/// Returns the given vector as normal with unit length. Zero vectors remain zero.
+///
+/// # Arguments
+/// * `v` - The vector to normalize.
+fn normalize_or_zero(v: &Vec3) -> Point3D {
+    let length = v.norm();
+    if length > 0f32 {
+        Point3D(v / length)
+    } else {
+        Point3D(*v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::morph_targets::MorphTarget, *};
+
+    /// Creates a unit cube translated by the given offset.
+    ///
+    /// # Arguments
+    /// * `offset` - The translation of the cube.
+    /// * `flip` - If true, the winding order of all triangles is flipped.
+    fn create_cube(offset: Vec3, flip: bool) -> Mesh {
+        let positions: Vec<Point3D> = [
+            [-0.5, -0.5, 0.5],
+            [0.5, -0.5, 0.5],
+            [-0.5, 0.5, 0.5],
+            [0.5, 0.5, 0.5],
+            [-0.5, 0.5, -0.5],
+            [0.5, 0.5, -0.5],
+            [-0.5, -0.5, -0.5],
+            [0.5, -0.5, -0.5],
+        ]
+        .iter()
+        .map(|p| Point3D(Vec3::new(p[0], p[1], p[2]) + offset))
+        .collect();
+
+        let quads = [
+            [0, 1, 3, 2],
+            [2, 3, 5, 4],
+            [4, 5, 7, 6],
+            [6, 7, 1, 0],
+            [1, 7, 5, 3],
+            [6, 0, 2, 4],
+        ];
+
+        let mut indices = Vec::new();
+        for q in quads.iter() {
+            if flip {
+                indices.extend_from_slice(&[q[0], q[2], q[1], q[0], q[3], q[2]]);
+            } else {
+                indices.extend_from_slice(&[q[0], q[1], q[2], q[0], q[2], q[3]]);
+            }
+        }
+
+        let primitives =
+            Primitives::new(IndexData::Indices(indices), PrimitiveType::Triangles).unwrap();
+
+        Mesh::new(Vertices::from_positions(positions), primitives).unwrap()
+    }
+
+    #[test]
+    fn test_is_outward_oriented() {
+        assert!(create_cube(Vec3::zeros(), false).is_outward_oriented());
+        assert!(!create_cube(Vec3::zeros(), true).is_outward_oriented());
+    }
+
+    #[test]
+    fn test_is_outward_oriented_translated_cube() {
+        let offset = Vec3::new(1000f32, -500f32, 250f32);
+
+        assert!(create_cube(offset, false).is_outward_oriented());
+        assert!(!create_cube(offset, true).is_outward_oriented());
+    }
+
+    #[test]
+    fn test_is_outward_oriented_without_triangles() {
+        let positions = vec![
+            Point3D::new(0f32, 0f32, 0f32),
+            Point3D::new(1f32, 0f32, 0f32),
+        ];
+        let primitives = Primitives::new(IndexData::NonIndexed(2), PrimitiveType::Line).unwrap();
+        let mesh = Mesh::new(Vertices::from_positions(positions), primitives).unwrap();
+
+        assert!(!mesh.is_outward_oriented());
+    }
+
+    #[test]
+    fn test_is_outward_oriented_static_pose() {
+        // the morph target mirrors the cube at its center, which turns the triangles inwards
+        let mut mesh = create_cube(Vec3::zeros(), false);
+        let deltas = mesh
+            .get_vertices()
+            .get_positions()
+            .iter()
+            .map(|p| Point3D(p.0 * -2f32))
+            .collect();
+        let morph_targets =
+            MorphTargets::new(vec![MorphTarget::new(Some(deltas), None)], vec![0f32]).unwrap();
+        mesh.set_morph_targets(morph_targets).unwrap();
+        assert!(mesh.is_outward_oriented());
+
+        let mut morph_targets = mesh.get_morph_targets().unwrap().clone();
+        morph_targets.set_weights(vec![1f32]).unwrap();
+        mesh.set_morph_targets(morph_targets).unwrap();
+        assert!(!mesh.is_outward_oriented());
+    }
+
+    #[test]
+    fn test_voxel_downsample_static_pose() {
+        let positions = vec![
+            Point3D::new(0.1f32, 0.1f32, 0.1f32),
+            Point3D::new(0.2f32, 0.2f32, 0.2f32),
+        ];
+        let deltas = vec![
+            Point3D::new(0f32, 0f32, 0f32),
+            Point3D::new(1f32, 0f32, 0f32),
+        ];
+        let primitives = Primitives::new(IndexData::NonIndexed(2), PrimitiveType::Point).unwrap();
+        let mut mesh = Mesh::new(Vertices::from_positions(positions), primitives).unwrap();
+        let morph_targets =
+            MorphTargets::new(vec![MorphTarget::new(Some(deltas), None)], vec![1f32]).unwrap();
+        mesh.set_morph_targets(morph_targets).unwrap();
+
+        // the displaced point moves into its own voxel
+        let downsampled = mesh.voxel_downsample(0.5f32).unwrap();
+        assert_eq!(downsampled.get_vertices().len(), 2);
+    }
+
+    #[test]
+    fn test_voxel_downsample() {
+        // create a dense grid of 10x10x10 points inside the box [0,1]^3
+        let mut positions = Vec::new();
+        let mut colors = Vec::new();
+        let mut normals = Vec::new();
+        for x in 0..10 {
+            for y in 0..10 {
+                for z in 0..10 {
+                    let p = Vec3::new(x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5) * 0.1;
+                    positions.push(Point3D(p));
+                    colors.push(RGBA::new(p.x, p.y, p.z, 1f32));
+                    normals.push(Point3D::new(0f32, 0f32, 2f32));
+                }
+            }
+        }
+
+        let mut vertices = Vertices::from_positions(positions);
+        vertices.set_colors(colors).unwrap();
+        vertices.set_normals(normals).unwrap();
+        let primitives =
+            Primitives::new(IndexData::NonIndexed(1000), PrimitiveType::Point).unwrap();
+        let mesh = Mesh::new(vertices, primitives).unwrap();
+
+        // a voxel size of 0.5 results into 2x2x2 occupied voxels
+        let downsampled = mesh.voxel_downsample(0.5f32).unwrap();
+        let vertices = downsampled.get_vertices();
+        let primitives = downsampled.get_primitives();
+        assert_eq!(vertices.len(), 8);
+        assert_eq!(primitives.get_primitive_type(), PrimitiveType::Point);
+        assert_eq!(primitives.num_primitives(), 8);
+
+        // the representative points are the centroids of the points inside each voxel
+        let colors = vertices.get_colors().unwrap();
+        let normals = vertices.get_normals().unwrap();
+        for (i, p) in vertices.get_positions().iter().enumerate() {
+            for k in 0..3 {
+                let c = p.0[k];
+                assert!((c - 0.25f32).abs() < 1e-5f32 || (c - 0.75f32).abs() < 1e-5f32);
+                assert!((colors[i].0[k] - c).abs() < 1e-5f32);
+            }
+
+            assert!((colors[i].0[3] - 1f32).abs() < 1e-5f32);
+            assert_eq!(normals[i], Point3D::new(0f32, 0f32, 1f32));
+        }
+
+        // a voxel size of 0.1 keeps all points as each point has its own voxel
+        let downsampled = mesh.voxel_downsample(0.1f32).unwrap();
+        assert_eq!(downsampled.get_vertices().len(), 1000);
+
+        // a voxel size larger than the grid results into a single point
+        let downsampled = mesh.voxel_downsample(10f32).unwrap();
+        assert_eq!(downsampled.get_vertices().len(), 1);
+
+        // invalid voxel sizes are rejected
+        for voxel_size in [0f32, -1f32, f32::NAN] {
+            match mesh.voxel_downsample(voxel_size) {
+                Err(Error::InvalidArgument(_)) => {}
+                _ => panic!("Expected invalid argument error"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_transform() {
+        let offset = Vec3::new(1f32, 2f32, 3f32);
+        let mesh = create_cube(Vec3::zeros(), false);
+
+        let m = nalgebra_glm::translation(&offset);
+        let transformed = mesh.transform(&m).unwrap();
+        for (p, q) in mesh
+            .get_vertices()
+            .get_positions()
+            .iter()
+            .zip(transformed.get_vertices().get_positions().iter())
+        {
+            assert_eq!(p.0 + offset, q.0);
+        }
+
+        // mirroring keeps the orientation of the triangles
+        let m = nalgebra_glm::scaling(&Vec3::new(-1f32, 1f32, 1f32));
+        let mirrored = mesh.transform(&m).unwrap();
+        assert!(mirrored.is_outward_oriented());
+        assert_eq!(mirrored.get_primitives().num_primitives(), 12);
+    }
+
+    #[test]
+    fn test_weld_vertices() {
+        // split the cube such that every triangle has its own vertices
+        let mesh = create_cube(Vec3::zeros(), false);
+        let indices = mesh.get_primitives().get_raw_index_data().to_indices();
+        let vertices = mesh.get_vertices().select(&indices);
+        let primitives = Primitives::new(
+            IndexData::NonIndexed(indices.len()),
+            PrimitiveType::Triangles,
+        )
+        .unwrap();
+        let split_mesh = Mesh::new(vertices, primitives).unwrap();
+        assert_eq!(split_mesh.get_vertices().len(), 36);
+
+        let welded = split_mesh.weld_vertices(1e-5f32).unwrap();
+        assert_eq!(welded.get_vertices().len(), 8);
+        assert_eq!(welded.get_primitives().num_primitives(), 12);
+        assert!(welded.is_outward_oriented());
+    }
+
+    #[test]
+    fn test_compute_smooth_normals() {
+        let mesh = create_cube(Vec3::zeros(), false);
+        let normals = mesh.compute_smooth_normals();
+        assert_eq!(normals.len(), 8);
+
+        // the smooth normals of a cube centered at the origin point into the octant of the corner
+        for (n, p) in normals.iter().zip(mesh.get_vertices().get_positions()) {
+            assert!((n.0.norm() - 1f32).abs() < 1e-5f32);
+            for k in 0..3 {
+                assert_eq!(n.0[k].signum(), p.0[k].signum());
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_flat_shaded() {
+        let mesh = create_cube(Vec3::zeros(), false);
+        let flat = mesh.to_flat_shaded().unwrap();
+
+        assert_eq!(flat.get_vertices().len(), 36);
+        assert_eq!(flat.get_primitives().num_primitives(), 12);
+        assert!(flat.is_outward_oriented());
+
+        // each triangle of the cube gets the normal of the cube side it belongs to
+        let positions = flat.get_vertices().get_positions();
+        let normals = flat.get_vertices().get_normals().unwrap();
+        for (p, n) in positions.iter().zip(normals.iter()) {
+            let side = n.0.iamax();
+            assert_eq!(n.0[side].abs(), 1f32);
+            assert_eq!(n.0.norm(), 1f32);
+            assert_eq!(p.0[side], n.0[side] * 0.5f32);
+        }
+    }
 }
@@ -1,14 +1,18 @@
 mod component;
 mod mesh;
+mod morph_targets;
 mod primitives;
 mod vertices;
 
 mod material;
 mod shape;
+mod triangle;
 
-pub use component::{Component, Float, Normal, Point3D};
-pub use material::{Material, PhongMaterialData};
+pub use component::{Component, Float, Normal, Point2D, Point3D, TexCoord};
+pub use material::{Material, PhongMaterialData, TextureReference};
 pub use mesh::Mesh;
+pub use morph_targets::{MorphTarget, MorphTargets};
 pub use primitives::{PrimitiveType, Primitives, IndexData};
 pub use shape::{Shape, ShapePart};
-pub use vertices::{Colors, Normals, Positions, Vertices};
+pub use triangle::Triangle;
+pub use vertices::{Colors, Normals, Positions, TexCoords, Vertices};
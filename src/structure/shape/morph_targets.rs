@@ -0,0 +1,169 @@
+use nalgebra_glm::Mat3;
+
+use crate::error::Error;
+
+use super::{
+    component::Point3D,
+    vertices::{Normals, Positions},
+};
+
+/// A single morph target, i.e., per vertex displacements of the base vertices of a mesh.
+#[derive(Clone)]
+pub struct MorphTarget {
+    position_deltas: Option<Positions>,
+    normal_deltas: Option<Normals>,
+}
+
+impl MorphTarget {
+    /// Creates a new morph target from the given displacements.
+    ///
+    /// # Arguments
+    /// * `position_deltas` - The optional displacements of the vertex positions.
+    /// * `normal_deltas` - The optional displacements of the vertex normals.
+    pub fn new(position_deltas: Option<Positions>, normal_deltas: Option<Normals>) -> Self {
+        Self {
+            position_deltas,
+            normal_deltas,
+        }
+    }
+
+    /// Returns a reference onto the displacements of the vertex positions.
+    pub fn get_position_deltas(&self) -> Option<&Positions> {
+        self.position_deltas.as_ref()
+    }
+
+    /// Returns a reference onto the displacements of the vertex normals.
+    pub fn get_normal_deltas(&self) -> Option<&Normals> {
+        self.normal_deltas.as_ref()
+    }
+}
+
+/// The morph targets of a mesh together with their weights. The weights define the static
+/// pose of the mesh, i.e., the base vertices plus the weighted displacements of all targets.
+#[derive(Clone)]
+pub struct MorphTargets {
+    targets: Vec<MorphTarget>,
+    weights: Vec<f32>,
+}
+
+impl MorphTargets {
+    /// Creates new morph targets. If the number of weights does not match the number of
+    /// targets, an error is returned.
+    ///
+    /// # Arguments
+    /// * `targets` - The morph targets.
+    /// * `weights` - The weight for each morph target.
+    pub fn new(targets: Vec<MorphTarget>, weights: Vec<f32>) -> Result<Self, Error> {
+        let mut morph_targets = Self {
+            targets,
+            weights: Vec::new(),
+        };
+        morph_targets.set_weights(weights)?;
+
+        Ok(morph_targets)
+    }
+
+    /// Returns a reference onto the morph targets.
+    pub fn get_targets(&self) -> &[MorphTarget] {
+        &self.targets
+    }
+
+    /// Returns a reference onto the weights of the morph targets.
+    pub fn get_weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    /// Sets the weights of the morph targets. If the number of weights does not match the
+    /// number of targets, an error is returned.
+    ///
+    /// # Arguments
+    /// * `weights` - The weight for each morph target.
+    pub fn set_weights(&mut self, weights: Vec<f32>) -> Result<(), Error> {
+        if weights.len() != self.targets.len() {
+            return Err(Error::InvalidArgument(format!(
+                "Got {} morph targets, but {} weights",
+                self.targets.len(),
+                weights.len()
+            )));
+        }
+
+        self.weights = weights;
+        Ok(())
+    }
+
+    /// Returns true if all weights are zero, i.e., the static pose equals the base vertices.
+    pub fn has_zero_weights(&self) -> bool {
+        self.weights.iter().all(|w| *w == 0f32)
+    }
+
+    /// Returns new morph targets consisting of the displacements at the given indices, i.e.,
+    /// the i-th returned displacement is a copy of the displacement referenced by the i-th index.
+    ///
+    /// # Arguments
+    /// * `indices` - The indices of the vertices to select. Must be in range.
+    pub fn select(&self, indices: &[u32]) -> MorphTargets {
+        let select_deltas = |deltas: &Positions| -> Positions {
+            indices.iter().map(|i| deltas[*i as usize]).collect()
+        };
+
+        let targets = self
+            .targets
+            .iter()
+            .map(|t| MorphTarget {
+                position_deltas: t.position_deltas.as_ref().map(select_deltas),
+                normal_deltas: t.normal_deltas.as_ref().map(select_deltas),
+            })
+            .collect();
+
+        MorphTargets {
+            targets,
+            weights: self.weights.clone(),
+        }
+    }
+
+    /// Returns new morph targets with the position displacements only, e.g., for meshes whose
+    /// normals have been regenerated and no longer match the normal displacements.
+    pub fn without_normal_deltas(&self) -> MorphTargets {
+        let targets = self
+            .targets
+            .iter()
+            .map(|t| MorphTarget {
+                position_deltas: t.position_deltas.clone(),
+                normal_deltas: None,
+            })
+            .collect();
+
+        MorphTargets {
+            targets,
+            weights: self.weights.clone(),
+        }
+    }
+
+    /// Returns new morph targets with all displacements transformed by the given matrices.
+    ///
+    /// # Arguments
+    /// * `m` - The linear transformation applied to the position displacements.
+    /// * `normal_matrix` - The transformation applied to the normal displacements.
+    pub fn transform(&self, m: &Mat3, normal_matrix: &Mat3) -> MorphTargets {
+        let transform_deltas = |deltas: &Positions, m: &Mat3| -> Positions {
+            deltas.iter().map(|d| Point3D(m * d.0)).collect()
+        };
+
+        let targets = self
+            .targets
+            .iter()
+            .map(|t| MorphTarget {
+                position_deltas: t.position_deltas.as_ref().map(|d| transform_deltas(d, m)),
+                normal_deltas: t
+                    .normal_deltas
+                    .as_ref()
+                    .map(|d| transform_deltas(d, normal_matrix)),
+            })
+            .collect();
+
+        MorphTargets {
+            targets,
+            weights: self.weights.clone(),
+        }
+    }
+}
@@ -39,9 +39,19 @@ impl IndexData {
             _ => None,
         }
     }
+
+    /// Returns a copy of the indices. Non-indexed data is expanded to the natural order of the
+    /// vertices.
+    pub fn to_indices(&self) -> Vec<u32> {
+        match self {
+            IndexData::NonIndexed(n) => (0..*n as u32).collect(),
+            IndexData::Indices(indices) => indices.clone(),
+        }
+    }
 }
 
 /// The primitives defined by its indices.
+#[derive(Clone)]
 pub struct Primitives {
     /// The primitive type of the index data
     primitive_type: PrimitiveType,
@@ -136,6 +146,38 @@ impl Primitives {
             IndexData::NonIndexed(n) => if *n == 0 { None } else { Some((*n  - 1) as u32) }
         }
     }
+
+    /// Returns the vertex indices of all triangles defined by the primitives. Triangle strips
+    /// and fans are expanded into separate triangles while preserving their winding order.
+    /// Point and line primitives do not define any triangles and an empty list is returned.
+    pub fn to_triangles(&self) -> Vec<[u32; 3]> {
+        let indices = self.index_data.to_indices();
+
+        match self.primitive_type {
+            PrimitiveType::Triangles => indices
+                .chunks_exact(3)
+                .map(|t| [t[0], t[1], t[2]])
+                .collect(),
+            PrimitiveType::TriangleStrip => indices
+                .windows(3)
+                .enumerate()
+                .map(|(i, t)| {
+                    // every second triangle of a strip has a flipped winding order
+                    if i % 2 == 0 {
+                        [t[0], t[1], t[2]]
+                    } else {
+                        [t[1], t[0], t[2]]
+                    }
+                })
+                .collect(),
+            PrimitiveType::TriangleFan => indices
+                .windows(2)
+                .skip(1)
+                .map(|t| [indices[0], t[0], t[1]])
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -165,4 +207,19 @@ mod tests {
         let p = Primitives::new(IndexData::Indices(vec![1,2,3,4,5,6]), PrimitiveType::TriangleFan).unwrap();
         assert_eq!(p.num_primitives(), 4);
     }
+
+    #[test]
+    fn test_to_triangles() {
+        let p = Primitives::new(IndexData::Indices(vec![1,2,3,4,5,6]), PrimitiveType::Triangles).unwrap();
+        assert_eq!(p.to_triangles(), [[1,2,3], [4,5,6]]);
+
+        let p = Primitives::new(IndexData::NonIndexed(5), PrimitiveType::TriangleStrip).unwrap();
+        assert_eq!(p.to_triangles(), [[0,1,2], [2,1,3], [2,3,4]]);
+
+        let p = Primitives::new(IndexData::Indices(vec![1,2,3,4,5]), PrimitiveType::TriangleFan).unwrap();
+        assert_eq!(p.to_triangles(), [[1,2,3], [1,3,4], [1,4,5]]);
+
+        let p = Primitives::new(IndexData::Indices(vec![1,2,3,4]), PrimitiveType::Line).unwrap();
+        assert!(p.to_triangles().is_empty());
+    }
 }
\ No newline at end of file
@@ -1,8 +1,10 @@
 use std::rc::Rc;
 
+use nalgebra_glm::{determinant, mat4_to_mat3, Mat4};
+
 use crate::basic_types::{IDCounter, ID};
 
-use super::{material::Material, mesh::Mesh};
+use super::{component::Point3D, material::Material, mesh::Mesh, triangle::Triangle};
 
 static ID_COUNTER: IDCounter = IDCounter::new();
 
@@ -26,6 +28,46 @@ impl ShapePart {
     pub fn get_material(&self) -> Rc<Material> {
         self.material.clone()
     }
+
+    /// Returns the triangles of the part with the positions transformed by the given matrix. If
+    /// the matrix mirrors the geometry, the winding order is flipped to keep the triangles
+    /// counter-clockwise.
+    ///
+    /// # Arguments
+    /// * `transform` - The transformation applied onto the positions.
+    fn transform_triangles(&self, transform: &Mat4) -> Vec<Triangle> {
+        // use the static pose of the mesh, which cannot fail as the morph targets are validated
+        let posed_mesh = match self.mesh.get_morph_targets() {
+            Some(morph_targets) if !morph_targets.has_zero_weights() => {
+                self.mesh.to_static_pose().ok()
+            }
+            _ => None,
+        };
+        let mesh = posed_mesh.as_ref().unwrap_or(&self.mesh);
+
+        let positions: Vec<Point3D> = mesh
+            .get_vertices()
+            .get_positions()
+            .iter()
+            .map(|p| Point3D((transform * p.0.push(1f32)).xyz()))
+            .collect();
+        let flip = determinant(&mat4_to_mat3(transform)) < 0f32;
+
+        mesh.get_primitives()
+            .to_triangles()
+            .iter()
+            .map(|t| {
+                let (i1, i2) = if flip { (t[2], t[1]) } else { (t[1], t[2]) };
+                let corners = [
+                    positions[t[0] as usize],
+                    positions[i1 as usize],
+                    positions[i2 as usize],
+                ];
+
+                Triangle::new(corners, self.material.clone())
+            })
+            .collect()
+    }
 }
 
 /// A shape is the geometric and visual description of an object. A object is the instantiation
@@ -67,6 +109,22 @@ impl Shape {
     pub fn get_parts(&self) -> &[ShapePart] {
         &self.parts
     }
+
+    /// Returns an iterator over the world space triangles of all parts, i.e., strips and fans
+    /// are expanded into single triangles and the positions are transformed by the given node
+    /// transformation. Morph targets are applied with their weights and primitives which are
+    /// not triangle based are skipped. The triangles keep their orientation, also for mirroring
+    /// transformations.
+    ///
+    /// # Arguments
+    /// * `node_transform` - The accumulated transformation of the node the shape is attached to.
+    pub fn world_triangles(&self, node_transform: &Mat4) -> impl Iterator<Item = Triangle> + '_ {
+        let node_transform = *node_transform;
+
+        self.parts
+            .iter()
+            .flat_map(move |part| part.transform_triangles(&node_transform))
+    }
 }
 
 impl PartialEq for Shape {
@@ -76,3 +134,52 @@ impl PartialEq for Shape {
 }
 
 impl Eq for Shape {}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::{cross, Vec3};
+
+    use crate::structure::{IndexData, PrimitiveType, Primitives, Vertices};
+
+    use super::*;
+
+    #[test]
+    fn test_world_triangles_mirrored() {
+        // a single counter-clockwise triangle facing towards +z
+        let positions = vec![
+            Point3D::new(0f32, 0f32, 0f32),
+            Point3D::new(1f32, 0f32, 0f32),
+            Point3D::new(0f32, 1f32, 0f32),
+        ];
+        let vertices = Vertices::from_positions(positions);
+        let primitives =
+            Primitives::new(IndexData::NonIndexed(3), PrimitiveType::Triangles).unwrap();
+        let mesh = Mesh::new(vertices, primitives).unwrap();
+
+        let mut shape = Shape::new();
+        shape.add_part(ShapePart::new(Rc::new(mesh), Rc::new(Material::None)));
+
+        let compute_normal = |t: &Triangle| -> Vec3 {
+            let [p0, p1, p2] = t.get_positions();
+            cross(&(p1.0 - p0.0), &(p2.0 - p0.0))
+        };
+
+        // the identity keeps the order of the corners
+        let triangles: Vec<Triangle> = shape.world_triangles(&Mat4::identity()).collect();
+        assert_eq!(triangles.len(), 1);
+        assert_eq!(compute_normal(&triangles[0]), Vec3::new(0f32, 0f32, 1f32));
+
+        // mirroring at the yz-plane flips the corners to keep the triangle counter-clockwise
+        let m = nalgebra_glm::scaling(&Vec3::new(-1f32, 1f32, 1f32));
+        let triangles: Vec<Triangle> = shape.world_triangles(&m).collect();
+        assert_eq!(
+            triangles[0].get_positions(),
+            &[
+                Point3D::new(0f32, 0f32, 0f32),
+                Point3D::new(0f32, 1f32, 0f32),
+                Point3D::new(-1f32, 0f32, 0f32),
+            ]
+        );
+        assert_eq!(compute_normal(&triangles[0]), Vec3::new(0f32, 0f32, 1f32));
+    }
+}
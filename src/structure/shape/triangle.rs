@@ -0,0 +1,42 @@
+use std::rc::Rc;
+
+use nalgebra_glm::cross;
+
+use super::{component::Point3D, material::Material};
+
+/// A single triangle with the material of the shape part it belongs to.
+#[derive(Clone)]
+pub struct Triangle {
+    positions: [Point3D; 3],
+    material: Rc<Material>,
+}
+
+impl Triangle {
+    /// Creates a new triangle from the given corner positions and material.
+    ///
+    /// # Arguments
+    /// * `positions` - The positions of the three corners in counter-clockwise order.
+    /// * `material` - The material of the triangle.
+    pub fn new(positions: [Point3D; 3], material: Rc<Material>) -> Self {
+        Self {
+            positions,
+            material,
+        }
+    }
+
+    /// Returns a reference onto the positions of the three corners.
+    pub fn get_positions(&self) -> &[Point3D; 3] {
+        &self.positions
+    }
+
+    /// Returns the material of the triangle.
+    pub fn get_material(&self) -> Rc<Material> {
+        self.material.clone()
+    }
+
+    /// Returns the area of the triangle.
+    pub fn get_area(&self) -> f32 {
+        let [p0, p1, p2] = &self.positions;
+        nalgebra_glm::l2_norm(&cross(&(p1.0 - p0.0), &(p2.0 - p0.0))) * 0.5f32
+    }
+}
@@ -1,17 +1,20 @@
 use crate::{basic_types::RGBA, error::Error};
 
-use super::component::{Normal, Point3D};
+use super::component::{Normal, Point3D, TexCoord};
 
 pub type Positions = Vec<Point3D>;
 pub type Normals = Vec<Normal>;
 pub type Colors = Vec<RGBA>;
+pub type TexCoords = Vec<TexCoord>;
 
 /// Vertices contains a vertex list. A vertex is a position in space with additional optional
 /// attributes like normals, color, ... etc.
+#[derive(Clone)]
 pub struct Vertices {
     positions: Positions,
     normals: Option<Normals>,
     colors: Option<Colors>,
+    tex_coords: Vec<TexCoords>,
 }
 
 impl Vertices {
@@ -21,6 +24,7 @@ impl Vertices {
             positions: Vec::new(),
             normals: None,
             colors: None,
+            tex_coords: Vec::new(),
         }
     }
 
@@ -33,6 +37,7 @@ impl Vertices {
             positions,
             normals: None,
             colors: None,
+            tex_coords: Vec::new(),
         }
     }
 
@@ -41,6 +46,24 @@ impl Vertices {
         self.positions.len()
     }
 
+    /// Replaces the positions attribute. If the number of positions does not match the number
+    /// of vertices, an error is returned.
+    ///
+    /// # Arguments
+    /// * `positions` - The positions attribute to set.
+    pub fn set_positions(&mut self, positions: Positions) -> Result<(), Error> {
+        if self.positions.len() != positions.len() {
+            Err(Error::InvalidArgument(format!(
+                "Got {} vertices, but position attribute has {} entries",
+                self.positions.len(),
+                positions.len()
+            )))
+        } else {
+            self.positions = positions;
+            Ok(())
+        }
+    }
+
     /// Sets the normal attribute. If the number of normals does not match the number
     /// of vertices, an error is returned.
     ///
@@ -77,6 +100,37 @@ impl Vertices {
         }
     }
 
+    /// Sets the texture coordinates attribute for the given channel. The channel must either
+    /// already exist or directly follow the last existing channel. If the number of texture
+    /// coordinates does not match the number of vertices, an error is returned.
+    ///
+    /// # Arguments
+    /// * `channel` - The index of the texture coordinate channel, e.g., 0 for TEXCOORD_0.
+    /// * `tex_coords` - The texture coordinates attribute to set.
+    pub fn set_tex_coords(&mut self, channel: usize, tex_coords: TexCoords) -> Result<(), Error> {
+        if self.positions.len() != tex_coords.len() {
+            return Err(Error::InvalidArgument(format!(
+                "Got {} vertices, but texture coordinate attribute only has {} entries",
+                self.positions.len(),
+                tex_coords.len()
+            )));
+        }
+
+        if channel < self.tex_coords.len() {
+            self.tex_coords[channel] = tex_coords;
+            Ok(())
+        } else if channel == self.tex_coords.len() {
+            self.tex_coords.push(tex_coords);
+            Ok(())
+        } else {
+            Err(Error::InvalidArgument(format!(
+                "Cannot set texture coordinate channel {}, only got {} channels",
+                channel,
+                self.tex_coords.len()
+            )))
+        }
+    }
+
     /// Returns a reference onto the positions attribute.
     pub fn get_positions(&self) -> &Positions {
         &self.positions
@@ -91,4 +145,120 @@ impl Vertices {
     pub fn get_colors(&self) -> Option<&Colors> {
         self.colors.as_ref()
     }
+
+    /// Returns new vertices consisting of the vertices at the given indices, i.e., the i-th
+    /// returned vertex is a copy of the vertex referenced by the i-th index. All attributes are
+    /// copied.
+    ///
+    /// # Arguments
+    /// * `indices` - The indices of the vertices to select. Must be in range.
+    pub fn select(&self, indices: &[u32]) -> Vertices {
+        fn select_attribute<T: Copy>(values: &[T], indices: &[u32]) -> Vec<T> {
+            indices.iter().map(|i| values[*i as usize]).collect()
+        }
+
+        Vertices {
+            positions: select_attribute(&self.positions, indices),
+            normals: self
+                .normals
+                .as_ref()
+                .map(|normals| select_attribute(normals, indices)),
+            colors: self
+                .colors
+                .as_ref()
+                .map(|colors| select_attribute(colors, indices)),
+            tex_coords: self
+                .tex_coords
+                .iter()
+                .map(|tex_coords| select_attribute(tex_coords, indices))
+                .collect(),
+        }
+    }
+
+    /// Returns the number of texture coordinate channels.
+    pub fn num_tex_coord_channels(&self) -> usize {
+        self.tex_coords.len()
+    }
+
+    /// Returns a reference onto the texture coordinates attribute of the given channel.
+    ///
+    /// # Arguments
+    /// * `channel` - The index of the texture coordinate channel.
+    pub fn get_tex_coords(&self, channel: usize) -> Option<&TexCoords> {
+        self.tex_coords.get(channel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tex_coord_channels() {
+        let positions = vec![
+            Point3D::new(0f32, 0f32, 0f32),
+            Point3D::new(1f32, 0f32, 0f32),
+        ];
+        let mut vertices = Vertices::from_positions(positions);
+        assert_eq!(vertices.num_tex_coord_channels(), 0);
+        assert!(vertices.get_tex_coords(0).is_none());
+
+        let uv0 = vec![TexCoord::new(0f32, 0f32), TexCoord::new(1f32, 0f32)];
+        let uv1 = vec![TexCoord::new(0.5f32, 0.5f32), TexCoord::new(1f32, 1f32)];
+
+        // channels must not have gaps and must match the number of vertices
+        assert!(vertices.set_tex_coords(1, uv1.clone()).is_err());
+        assert!(vertices
+            .set_tex_coords(0, vec![TexCoord::new(0f32, 0f32)])
+            .is_err());
+
+        vertices.set_tex_coords(0, uv0.clone()).unwrap();
+        vertices.set_tex_coords(1, uv1.clone()).unwrap();
+        assert_eq!(vertices.num_tex_coord_channels(), 2);
+        assert_eq!(vertices.get_tex_coords(0).unwrap(), &uv0);
+        assert_eq!(vertices.get_tex_coords(1).unwrap(), &uv1);
+
+        // replace an existing channel
+        vertices.set_tex_coords(0, uv1.clone()).unwrap();
+        assert_eq!(vertices.num_tex_coord_channels(), 2);
+        assert_eq!(vertices.get_tex_coords(0).unwrap(), &uv1);
+    }
+
+    #[test]
+    fn test_select() {
+        let positions = vec![
+            Point3D::new(0f32, 0f32, 0f32),
+            Point3D::new(1f32, 0f32, 0f32),
+            Point3D::new(2f32, 0f32, 0f32),
+        ];
+        let mut vertices = Vertices::from_positions(positions);
+        vertices
+            .set_colors(vec![
+                RGBA::new(0f32, 0f32, 0f32, 1f32),
+                RGBA::new(1f32, 0f32, 0f32, 1f32),
+                RGBA::new(0f32, 1f32, 0f32, 1f32),
+            ])
+            .unwrap();
+
+        let selected = vertices.select(&[2, 0, 2]);
+        assert_eq!(selected.len(), 3);
+        assert_eq!(
+            selected.get_positions(),
+            &[
+                Point3D::new(2f32, 0f32, 0f32),
+                Point3D::new(0f32, 0f32, 0f32),
+                Point3D::new(2f32, 0f32, 0f32),
+            ]
+        );
+        assert_eq!(
+            selected.get_colors().unwrap(),
+            &[
+                RGBA::new(0f32, 1f32, 0f32, 1f32),
+                RGBA::new(0f32, 0f32, 0f32, 1f32),
+                RGBA::new(0f32, 1f32, 0f32, 1f32),
+            ]
+        );
+        assert!(selected.get_normals().is_none());
+        assert_eq!(selected.num_tex_coord_channels(), 0);
+    }
 }